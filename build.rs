@@ -0,0 +1,7 @@
+fn main() {
+    // Only regenerate the protobuf wire types when the `protobuf` feature is enabled; bincode
+    // remains the default so a plain `cargo build` needs no protoc toolchain.
+    #[cfg(feature = "protobuf")]
+    prost_build::compile_protos(&["proto/kre.proto"], &["proto/"])
+        .expect("Failed to compile proto/kre.proto");
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+
+use crate::net::secure_channel::SecureChannelError;
+
+/// Crate-level error type for the networked protocol.
+///
+/// A per-client error during a round (a bad frame, a dropped connection, a forged message) no
+/// longer panics the server task; it is turned into this type, reported back on the round's
+/// channel, and causes the round to finish as `UpdateSearchRange::Abort` instead of taking down
+/// the whole process.
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Decode(Box<bincode::ErrorKind>),
+    UnexpectedMessage,
+    ClientDisconnected,
+    TagVerification,
+    InsufficientShares { have: usize, need: usize },
+    MissingCiphertexts { have: usize, need: usize },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::Decode(e) => write!(f, "Failed to decode message: {}", e),
+            Error::UnexpectedMessage => write!(f, "Received an unexpected message type"),
+            Error::ClientDisconnected => write!(f, "Client disconnected"),
+            Error::TagVerification => write!(f, "AEAD tag verification failed"),
+            Error::InsufficientShares { have, need } => write!(
+                f, "Only {} of the {} partial decryptions needed for reconstruction arrived", have, need,
+            ),
+            Error::MissingCiphertexts { have, need } => write!(
+                f, "Only {} of the {} parties' input ciphertexts arrived; aggregation needs every party's count", have, need,
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        if e.kind() == std::io::ErrorKind::UnexpectedEof {
+            Error::ClientDisconnected
+        } else {
+            Error::Io(e)
+        }
+    }
+}
+
+impl From<Box<bincode::ErrorKind>> for Error {
+    fn from(e: Box<bincode::ErrorKind>) -> Self {
+        Error::Decode(e)
+    }
+}
+
+impl From<SecureChannelError> for Error {
+    fn from(_: SecureChannelError) -> Self {
+        Error::TagVerification
+    }
+}
+
+impl From<crate::net::framing::FramingError> for Error {
+    fn from(e: crate::net::framing::FramingError) -> Self {
+        match e {
+            crate::net::framing::FramingError::Io(io) => Error::from(io),
+            crate::net::framing::FramingError::FrameTooLarge(_) => Error::Decode(
+                Box::new(bincode::ErrorKind::SizeLimit),
+            ),
+        }
+    }
+}
@@ -6,6 +6,7 @@ use futures::future::join_all;
 use tokio::sync::Mutex;
 use tokio::test;
 
+use crate::net::handshake::StaticKeyPair;
 use crate::net::netclient::NetworkClient;
 use crate::net::netserver::NetworkServer;
 use crate::party::dp_client::{DPClient, get_scale_sigmoid, NoiseLevel};
@@ -28,9 +29,12 @@ pub async fn kre_protocol_net<T, P, S>(server: PartyServer, parties: Vec<P>) ->
         P: PartyClientTrait<T> + 'static,
         S: PartyServerTrait,
 {
-    // Create a new network server instance
+    // Each run generates a fresh server identity key; a real deployment would distribute
+    // `server_static.public` to every party out-of-band ahead of time instead.
+    let server_static = StaticKeyPair::generate();
+    let server_public = server_static.public;
     let mut server = NetworkServer::<PartyServer>::new(
-        SERVER_ADDRESS, server, parties.len()).await.unwrap();
+        SERVER_ADDRESS, server, parties.len(), parties.len(), server_static).await.unwrap();
 
 
     let result = Arc::new(Mutex::new(None));
@@ -39,7 +43,7 @@ pub async fn kre_protocol_net<T, P, S>(server: PartyServer, parties: Vec<P>) ->
     for party in parties.into_iter() {
         let result = result.clone();
         let handle = tokio::spawn(async move {
-            let mut client = NetworkClient::<T, P>::new(party, SERVER_ADDRESS).await.unwrap();
+            let mut client = NetworkClient::<T, P>::new(party, SERVER_ADDRESS, &server_public).await.unwrap();
             let output = client.run_protocol().await.unwrap();
             let mut result = result.lock().await;
             if result.is_none() {
@@ -52,7 +56,7 @@ pub async fn kre_protocol_net<T, P, S>(server: PartyServer, parties: Vec<P>) ->
 
     server.init_connections().await.unwrap();
 
-    server.run_protocol().await;
+    server.run_protocol().await.unwrap();
     join_all(handles).await;
 
     tracing::debug!("Found kth-ranked element: {:?}", result);
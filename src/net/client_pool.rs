@@ -0,0 +1,102 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+
+use crate::net::secure_channel::KEY_SIZE;
+use crate::net::transport::{Channel, Transport};
+
+/// A connected client's carrier together with the channel state used to authenticate and
+/// encrypt every frame exchanged with that party, plus the liveness bookkeeping needed to
+/// survive a mid-protocol dropout. Generic over `Transport` so the same pool works whether
+/// parties are connected over real TCP sockets, an in-memory duplex pair, or a TLS-wrapped
+/// stream.
+pub(crate) struct ClientConn<C: Channel, T: Transport = TcpStream> {
+    pub(crate) stream: T,
+    // Encrypts frames sent from the server to this client.
+    pub(crate) tx_channel: C,
+    // Decrypts frames received from this client.
+    pub(crate) rx_channel: C,
+    pub(crate) party_id: usize,
+    pub(crate) connected_at: Instant,
+    pub(crate) last_seen: Instant,
+    pub(crate) alive: bool,
+}
+
+impl<C: Channel, T: Transport> ClientConn<C, T> {
+    /// `tx_key`/`rx_key` come from the handshake run against this connection (see
+    /// `net::handshake`), one independent key per direction so neither `Channel`'s nonce counter
+    /// is ever reused against the other.
+    pub(crate) fn new(stream: T, party_id: usize, tx_key: &[u8; KEY_SIZE], rx_key: &[u8; KEY_SIZE]) -> Self {
+        let now = Instant::now();
+        Self {
+            stream,
+            tx_channel: C::new(tx_key),
+            rx_channel: C::new(rx_key),
+            party_id,
+            connected_at: now,
+            last_seen: now,
+            alive: true,
+        }
+    }
+}
+
+/// Owns the connections to all parties and tracks which ones are still responsive.
+///
+/// A mid-protocol dropout (a client that stops answering) no longer hangs the server: callers
+/// wrap each per-client read in a timeout, call `mark_dead` when a client misses its deadline,
+/// and check `live_count` against the reconstruction threshold before continuing a round.
+pub(crate) struct ClientPool<C: Channel, T: Transport = TcpStream> {
+    clients: Vec<Arc<Mutex<ClientConn<C, T>>>>,
+    // Minimum number of live clients required to reconstruct a threshold-Paillier decryption.
+    threshold: usize,
+}
+
+impl<C: Channel, T: Transport> ClientPool<C, T> {
+    pub(crate) fn new(threshold: usize) -> Self {
+        Self { clients: Vec::new(), threshold }
+    }
+
+    pub(crate) fn push(&mut self, conn: ClientConn<C, T>) {
+        self.clients.push(Arc::new(Mutex::new(conn)));
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item=&Arc<Mutex<ClientConn<C, T>>>> {
+        self.clients.iter()
+    }
+
+    /// Marks the given party as dead after it missed a round's deadline.
+    pub(crate) async fn mark_dead(&self, party_id: usize) {
+        let mut conn = self.clients[party_id].lock().await;
+        conn.alive = false;
+        tracing::warn!("Party {} marked dead after missing the round deadline", party_id);
+    }
+
+    /// Records that a party answered in time.
+    pub(crate) async fn mark_seen(&self, party_id: usize) {
+        let mut conn = self.clients[party_id].lock().await;
+        conn.last_seen = Instant::now();
+    }
+
+    /// Returns the number of parties still considered alive.
+    pub(crate) async fn live_count(&self) -> usize {
+        let mut count = 0;
+        for client in &self.clients {
+            if client.lock().await.alive {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Returns `true` if too many parties have dropped out to reconstruct a threshold
+    /// decryption, i.e. the round must `Abort`.
+    pub(crate) async fn below_threshold(&self) -> bool {
+        self.live_count().await < self.threshold
+    }
+}
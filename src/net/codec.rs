@@ -0,0 +1,37 @@
+//! Picks the wire format used for `ServerMessage`/`ClientMessage`.
+//!
+//! Bincode remains the default so today's Rust-only deployments need no changes; enabling the
+//! `protobuf` feature switches every party over to the language-neutral schema in
+//! `proto/kre.proto` instead.
+
+use crate::error::Error;
+use crate::net::netclient::ClientMessage;
+use crate::net::netserver::ServerMessage;
+
+pub(crate) fn encode_server_message(msg: &ServerMessage) -> Result<Vec<u8>, Error> {
+    #[cfg(feature = "protobuf")]
+    return crate::net::wire::encode_server_message(msg);
+    #[cfg(not(feature = "protobuf"))]
+    return Ok(bincode::serialize(msg)?);
+}
+
+pub(crate) fn decode_server_message(bytes: &[u8]) -> Result<ServerMessage, Error> {
+    #[cfg(feature = "protobuf")]
+    return crate::net::wire::decode_server_message(bytes);
+    #[cfg(not(feature = "protobuf"))]
+    return Ok(bincode::deserialize(bytes)?);
+}
+
+pub(crate) fn encode_client_message(msg: &ClientMessage) -> Result<Vec<u8>, Error> {
+    #[cfg(feature = "protobuf")]
+    return crate::net::wire::encode_client_message(msg);
+    #[cfg(not(feature = "protobuf"))]
+    return Ok(bincode::serialize(msg)?);
+}
+
+pub(crate) fn decode_client_message(bytes: &[u8]) -> Result<ClientMessage, Error> {
+    #[cfg(feature = "protobuf")]
+    return crate::net::wire::decode_client_message(bytes);
+    #[cfg(not(feature = "protobuf"))]
+    return Ok(bincode::deserialize(bytes)?);
+}
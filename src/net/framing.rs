@@ -0,0 +1,47 @@
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// The length prefix used by `write_frame`/`read_frame`: a 4-byte big-endian payload length.
+const LENGTH_PREFIX_SIZE: usize = 4;
+
+/// Rejects any frame whose declared length exceeds this, so a corrupted or malicious length
+/// prefix cannot make us allocate an unbounded buffer.
+const MAX_FRAME_SIZE: u32 = 16 * 1024 * 1024;
+
+#[derive(Debug)]
+pub(crate) enum FramingError {
+    Io(std::io::Error),
+    FrameTooLarge(u32),
+}
+
+impl From<std::io::Error> for FramingError {
+    fn from(e: std::io::Error) -> Self {
+        FramingError::Io(e)
+    }
+}
+
+/// Writes `payload` as a single frame: a 4-byte big-endian length prefix followed by the bytes.
+pub(crate) async fn write_frame<W: AsyncWrite + Unpin>(writer: &mut W, payload: &[u8]) -> Result<(), FramingError> {
+    let len = u32::try_from(payload.len()).map_err(|_| FramingError::FrameTooLarge(u32::MAX))?;
+    if len > MAX_FRAME_SIZE {
+        return Err(FramingError::FrameTooLarge(len));
+    }
+    writer.write_all(&len.to_be_bytes()).await?;
+    writer.write_all(payload).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Reads one frame written by `write_frame`: the 4-byte length prefix, then exactly that many
+/// bytes, looping over `read_exact` so a frame split across multiple TCP segments is still
+/// reassembled correctly.
+pub(crate) async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Vec<u8>, FramingError> {
+    let mut len_bytes = [0u8; LENGTH_PREFIX_SIZE];
+    reader.read_exact(&mut len_bytes).await?;
+    let len = u32::from_be_bytes(len_bytes);
+    if len > MAX_FRAME_SIZE {
+        return Err(FramingError::FrameTooLarge(len));
+    }
+    let mut payload = vec![0u8; len as usize];
+    reader.read_exact(&mut payload).await?;
+    Ok(payload)
+}
@@ -0,0 +1,148 @@
+//! Gossip-based party membership: a small last-writer-wins CRDT keyed by party id, so parties
+//! can learn each other's addresses and liveness without a central registry, plus the
+//! stake-weighted shuffle used to lay the learned membership out into [`super::topology`]'s
+//! aggregation tree.
+//!
+//! Merging two [`Membership`]s (e.g. after exchanging them with a gossip peer) is commutative,
+//! associative and idempotent: whichever [`ContactInfo`] carries the higher `version` for a
+//! given party wins, with ties broken by address so concurrent merges of the same two versions
+//! always agree. There is no transport for actually exchanging `Membership`s between parties
+//! yet; that is still open work, same as `AggregationTopology::Tree`'s relaying.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// A party's last-known address and the version it was advertised under. Later (higher-version)
+/// `ContactInfo` always wins a merge, so a party can freely re-advertise after moving or
+/// recovering from a dropout.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ContactInfo {
+    pub(crate) address: String,
+    pub(crate) version: u64,
+}
+
+/// A last-writer-wins map from party id to [`ContactInfo`]. This is the whole gossip "protocol":
+/// parties periodically exchange their `Membership` with a random peer and [`Membership::merge`]
+/// the result, so address and liveness information eventually reaches everyone without any party
+/// needing a complete view up front.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Membership {
+    entries: HashMap<usize, ContactInfo>,
+}
+
+impl Membership {
+    pub(crate) fn new() -> Self {
+        Self { entries: HashMap::new() }
+    }
+
+    /// Records `info` as `party`'s contact info, keeping the existing entry if it is already at
+    /// least as new. Ties (equal version, different address) are broken by address so that
+    /// merging the same two updates in either order lands on the same winner everywhere.
+    pub(crate) fn merge(&mut self, party: usize, info: ContactInfo) {
+        match self.entries.get(&party) {
+            Some(existing) if Self::wins(existing, &info) != Ordering::Less => {}
+            _ => {
+                self.entries.insert(party, info);
+            }
+        }
+    }
+
+    /// Merges every entry of `other` into `self`, as if each had arrived as its own gossip
+    /// update.
+    pub(crate) fn merge_all(&mut self, other: &Membership) {
+        for (&party, info) in &other.entries {
+            self.merge(party, info.clone());
+        }
+    }
+
+    pub(crate) fn get(&self, party: usize) -> Option<&ContactInfo> {
+        self.entries.get(&party)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Compares `existing` against `candidate`, returning `Less` if `candidate` should replace
+    /// `existing`.
+    fn wins(existing: &ContactInfo, candidate: &ContactInfo) -> Ordering {
+        existing.version.cmp(&candidate.version).then_with(|| existing.address.cmp(&candidate.address))
+    }
+}
+
+/// Produces a stake-weighted ordering of `0..weights.len()` using the A-Res reservoir scheme:
+/// each index `i` draws `u_i` uniform in `(0, 1)` and gets key `k_i = u_i.powf(1.0 / w_i)`,
+/// and the indices are sorted descending by that key. Higher weight pushes `k_i` closer to 1,
+/// so heavier (e.g. lower-latency or higher-bandwidth) parties land earlier in the order with
+/// higher probability, while still letting any party end up anywhere.
+///
+/// Slicing the returned order into a `fan_out`-sized layer 1 (reporting to the server), a
+/// `fan_out^2`-sized layer 2 and so on gives the tree-layer assignment [`super::topology::AggregationTopology::Tree`]
+/// expects; an index's position in the returned `Vec` is exactly the `idx` it should be built
+/// with via `NetworkClient::new_with_topology`.
+pub(crate) fn weighted_shuffle(weights: &[f64], rng: &mut impl Rng) -> Vec<usize> {
+    let mut keyed: Vec<(f64, usize)> = weights
+        .iter()
+        .enumerate()
+        .map(|(i, &w)| {
+            let u: f64 = rng.gen_range(f64::EPSILON..1.0);
+            let key = u.powf(1.0 / w);
+            (key, i)
+        })
+        .collect();
+    keyed.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
+    keyed.into_iter().map(|(_, i)| i).collect()
+}
+
+#[test]
+fn test_merge_keeps_higher_version() {
+    let mut membership = Membership::new();
+    membership.merge(0, ContactInfo { address: "10.0.0.1:9000".into(), version: 1 });
+    membership.merge(0, ContactInfo { address: "10.0.0.2:9000".into(), version: 0 });
+    assert_eq!(membership.get(0).unwrap().address, "10.0.0.1:9000");
+
+    membership.merge(0, ContactInfo { address: "10.0.0.3:9000".into(), version: 2 });
+    assert_eq!(membership.get(0).unwrap().address, "10.0.0.3:9000");
+}
+
+#[test]
+fn test_merge_is_commutative_on_ties() {
+    let a = ContactInfo { address: "10.0.0.1:9000".into(), version: 1 };
+    let b = ContactInfo { address: "10.0.0.2:9000".into(), version: 1 };
+
+    let mut forward = Membership::new();
+    forward.merge(0, a.clone());
+    forward.merge(0, b.clone());
+
+    let mut backward = Membership::new();
+    backward.merge(0, b);
+    backward.merge(0, a);
+
+    assert_eq!(forward.get(0), backward.get(0));
+}
+
+#[test]
+fn test_weighted_shuffle_is_a_permutation() {
+    let mut rng = rand::thread_rng();
+    let weights = vec![1.0, 2.0, 0.5, 3.0, 1.0];
+    let mut order = weighted_shuffle(&weights, &mut rng);
+    order.sort();
+    assert_eq!(order, vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn test_weighted_shuffle_favors_heavier_parties() {
+    let mut rng = rand::thread_rng();
+    let weights = vec![0.01, 100.0];
+    let mut heavy_first = 0;
+    let trials = 200;
+    for _ in 0..trials {
+        if weighted_shuffle(&weights, &mut rng)[0] == 1 {
+            heavy_first += 1;
+        }
+    }
+    assert!(heavy_first as f64 / trials as f64 > 0.9);
+}
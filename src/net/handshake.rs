@@ -0,0 +1,117 @@
+//! An X25519 key-exchange handshake run once at connection time, replacing the pre-shared
+//! `client_keys` `NetworkServer::new` used to take with session keys that are fresh per
+//! connection and never transit the network.
+//!
+//! The client authenticates the server against a `server_static_public` key configured ahead of
+//! time (e.g. distributed alongside `server_addr`); a party able to accept the TCP connection but
+//! not holding the matching `StaticKeyPair::secret` cannot derive the session keys the real
+//! server would, so it cannot impersonate the coordination point. The reverse — the server
+//! authenticating the client — is not done here, since nothing in this crate tracks a per-party
+//! static identity yet; it would slot in as a second DH term the same way `dh_static` does below,
+//! once one does.
+//!
+//! Every session additionally folds in a fresh ephemeral key from both sides, so compromising
+//! `server_static_public`'s secret half only lets an attacker impersonate the server going
+//! forward, not decrypt past sessions.
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use x25519_dalek::{EphemeralSecret, PublicKey, SharedSecret, StaticSecret};
+
+use crate::error::Error;
+use crate::net::secure_channel::KEY_SIZE;
+
+/// A party's long-term identity key. Losing it only exposes future sessions to impersonation,
+/// never past ones, since every session also mixes in a fresh ephemeral key.
+pub(crate) struct StaticKeyPair {
+    secret: StaticSecret,
+    pub(crate) public: PublicKey,
+}
+
+impl StaticKeyPair {
+    pub(crate) fn generate() -> Self {
+        let secret = StaticSecret::random_from_rng(rand::thread_rng());
+        let public = PublicKey::from(&secret);
+        Self { secret, public }
+    }
+}
+
+/// The two independent keys one handshake derives, one per direction, so the two directions of a
+/// `SecureChannel` never share a nonce counter.
+pub(crate) struct SessionKeys {
+    pub(crate) tx_key: [u8; KEY_SIZE],
+    pub(crate) rx_key: [u8; KEY_SIZE],
+}
+
+const HANDSHAKE_INFO: &[u8] = b"dp-KRE handshake v1";
+
+/// Runs the client side of the handshake against `stream`, authenticating `server_static_public`.
+/// `server_handshake` is the mirror-image run on the accepting side.
+pub(crate) async fn client_handshake<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S, server_static_public: &PublicKey,
+) -> Result<SessionKeys, Error> {
+    // Used for two Diffie-Hellman operations below, which `EphemeralSecret` (consumed by its
+    // first `diffie_hellman`) does not allow; it is still freshly generated and dropped per session.
+    let client_ephemeral = StaticSecret::random_from_rng(rand::thread_rng());
+    let client_ephemeral_public = PublicKey::from(&client_ephemeral);
+    stream.write_all(client_ephemeral_public.as_bytes()).await?;
+
+    let server_ephemeral_public = read_public_key(stream).await?;
+
+    // Only a peer holding the secret half of `server_static_public` can reproduce `dh_static`,
+    // which is what authenticates the server to the client.
+    let dh_static = client_ephemeral.diffie_hellman(server_static_public);
+    let dh_ephemeral = client_ephemeral.diffie_hellman(&server_ephemeral_public);
+    Ok(derive_session_keys(&dh_static, &dh_ephemeral, Role::Client))
+}
+
+/// Runs the server side of the handshake over `stream`, proving possession of `server_static`'s
+/// secret key to whichever client is running `client_handshake` against its public half.
+pub(crate) async fn server_handshake<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S, server_static: &StaticKeyPair,
+) -> Result<SessionKeys, Error> {
+    let client_ephemeral_public = read_public_key(stream).await?;
+
+    let server_ephemeral = EphemeralSecret::random_from_rng(rand::thread_rng());
+    let server_ephemeral_public = PublicKey::from(&server_ephemeral);
+    stream.write_all(server_ephemeral_public.as_bytes()).await?;
+
+    let dh_static = server_static.secret.diffie_hellman(&client_ephemeral_public);
+    let dh_ephemeral = server_ephemeral.diffie_hellman(&client_ephemeral_public);
+    Ok(derive_session_keys(&dh_static, &dh_ephemeral, Role::Server))
+}
+
+async fn read_public_key<S: AsyncRead + Unpin>(stream: &mut S) -> Result<PublicKey, Error> {
+    let mut bytes = [0u8; 32];
+    stream.read_exact(&mut bytes).await?;
+    Ok(PublicKey::from(bytes))
+}
+
+enum Role {
+    Client,
+    Server,
+}
+
+/// Expands the two DH outputs into the pair of session keys, labeled so that the client's
+/// "I send with this" key is always the server's "I receive with this" key, regardless of which
+/// side is deriving them.
+fn derive_session_keys(dh_static: &SharedSecret, dh_ephemeral: &SharedSecret, role: Role) -> SessionKeys {
+    let mut ikm = Vec::with_capacity(64);
+    ikm.extend_from_slice(dh_static.as_bytes());
+    ikm.extend_from_slice(dh_ephemeral.as_bytes());
+
+    let hk = Hkdf::<Sha256>::new(None, &ikm);
+    let mut okm = [0u8; 2 * KEY_SIZE];
+    hk.expand(HANDSHAKE_INFO, &mut okm).expect("2*KEY_SIZE is a valid HKDF-SHA256 output length");
+
+    let (client_to_server, server_to_client) = okm.split_at(KEY_SIZE);
+    let (tx_key, rx_key) = match role {
+        Role::Client => (client_to_server, server_to_client),
+        Role::Server => (server_to_client, client_to_server),
+    };
+    SessionKeys {
+        tx_key: tx_key.try_into().expect("KEY_SIZE-length slice"),
+        rx_key: rx_key.try_into().expect("KEY_SIZE-length slice"),
+    }
+}
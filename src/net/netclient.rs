@@ -1,13 +1,18 @@
 use std::error::Error;
 use std::marker::PhantomData;
 
-use bincode::deserialize;
 use pht_crypto::{Ciphertext, paillier::PartialDecryption};
 use serde::{Deserialize, Serialize};
-use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::net::TcpStream;
+use x25519_dalek::PublicKey;
 
+use crate::net::codec;
+use crate::net::framing::{read_frame, write_frame};
+use crate::net::handshake::client_handshake;
 use crate::net::netserver::ServerMessage;
+use crate::net::secure_channel::SecureChannel;
+use crate::net::topology::AggregationTopology;
+use crate::net::transport::Transport;
 use crate::party::{TypeTrait, UpdateSearchRange};
 use crate::party::party_client::PartyClientTrait;
 
@@ -17,56 +22,151 @@ pub(crate) enum ClientMessage {
     MsgPartialDecryption(PartialDecryption, PartialDecryption),
 }
 
-pub(crate) fn parse_client_message(data: &[u8]) -> Result<ClientMessage, Box<dyn Error>> {
+pub(crate) fn parse_client_message(data: &[u8]) -> Result<ClientMessage, crate::error::Error> {
     // Deserialize the received data into one of the known message types
-    deserialize(data).map_err(|e| e.into())
+    codec::decode_client_message(data)
 }
 
-pub(crate) struct NetworkClient<T, C> where T: TypeTrait, C: PartyClientTrait<T> {
+pub(crate) struct NetworkClient<T, C, Tr = TcpStream> where T: TypeTrait, C: PartyClientTrait<T>, Tr: Transport {
     client: C,
     // The cryptographic party client implementation
-    stream: TcpStream,
-    // The TCP connection to the server
+    stream: Tr,
+    // The carrier connected to the server, or to this party's parent once tree relaying lands.
+    // Encrypts frames sent to the server; decrypts frames received from it. Keyed from the
+    // handshake run against `server_static_public` in every constructor below, never a
+    // pre-shared secret.
+    tx_channel: SecureChannel,
+    rx_channel: SecureChannel,
+    // This party's position in the aggregation tree: who it would forward its combined
+    // ciphertext to (`None` means directly to the server) and whose ciphertexts it would fold
+    // in first. Actually accepting connections from `children` and relaying to `parent` instead
+    // of `server_addr` requires a listener on the party side; `Transport` makes the carrier
+    // itself swappable, but wiring up that relaying is still open work.
+    parent: Option<usize>,
+    children: Vec<usize>,
     phantom: PhantomData<T>,
 }
 
-impl<T, C> NetworkClient<T, C>
+impl<T, C> NetworkClient<T, C, TcpStream>
     where
         T: TypeTrait,
         C: PartyClientTrait<T>,
 {
-    // Create a new NetworkClient instance and establish a TCP connection to the specified server address.
-    pub async fn new(client: C, server_addr: &str) -> Result<Self, Box<dyn Error>> where T: TypeTrait, C: PartyClientTrait<T> {
-        // Establish a TCP connection to the specified server address
-        let stream = TcpStream::connect(server_addr).await?;
+    // Create a new NetworkClient instance, establish a TCP connection to the specified server
+    // address, and run the handshake that authenticates `server_static_public` and derives this
+    // connection's session keys.
+    pub async fn new(client: C, server_addr: &str, server_static_public: &PublicKey) -> Result<Self, Box<dyn Error>> where T: TypeTrait, C: PartyClientTrait<T> {
+        // Establish a TCP connection to the specified server address. With the `dnssec` feature,
+        // `server_addr`'s host is resolved through a DNSSEC-validating resolver first instead of
+        // trusting the OS stub resolver, so party discovery can't be steered by a spoofed answer.
+        let mut stream = Self::connect(server_addr).await?;
+        let session = client_handshake(&mut stream, server_static_public).await?;
         // Return the constructed NetworkClient instance
-        Ok(Self { client, stream, phantom: Default::default() })
+        Ok(Self {
+            client,
+            stream,
+            tx_channel: SecureChannel::new(&session.tx_key),
+            rx_channel: SecureChannel::new(&session.rx_key),
+            parent: None,
+            children: Vec::new(),
+            phantom: Default::default(),
+        })
+    }
+
+    /// Creates a `NetworkClient` that knows its place (`idx` of `n`) in the given aggregation
+    /// topology, so a future relaying transport can fold its children's ciphertexts before
+    /// forwarding to its parent instead of talking to the server directly.
+    pub async fn new_with_topology(client: C, server_addr: &str, server_static_public: &PublicKey, idx: usize, n: usize, topology: AggregationTopology) -> Result<Self, Box<dyn Error>> {
+        let mut stream = Self::connect(server_addr).await?;
+        let session = client_handshake(&mut stream, server_static_public).await?;
+        let parent = topology.parent_of(idx);
+        let children = topology.children_of(idx, n);
+        tracing::trace!("Party {idx} placed in {topology:?}: parent={parent:?}, children={children:?}");
+        Ok(Self {
+            client,
+            stream,
+            tx_channel: SecureChannel::new(&session.tx_key),
+            rx_channel: SecureChannel::new(&session.rx_key),
+            parent,
+            children,
+            phantom: Default::default(),
+        })
+    }
+
+    // Resolves and connects to `server_addr` ("host:port"). Behind the `dnssec` feature, the
+    // host is resolved through `net::resolver::DnssecResolver` first, rejecting the connection
+    // if DNSSEC validation fails; a fresh resolver handle is built per call today rather than
+    // threaded through as a long-lived handle, since `NetworkClient` has no reconnect loop yet
+    // that would benefit from reusing one.
+    async fn connect(server_addr: &str) -> Result<TcpStream, Box<dyn Error>> {
+        #[cfg(feature = "dnssec")]
+        {
+            let (host, port) = server_addr.rsplit_once(':')
+                .ok_or("server_addr must be in \"host:port\" form")?;
+            let port: u16 = port.parse()?;
+            let resolver = crate::net::resolver::DnssecResolver::new()?;
+            let addr = resolver.resolve(host, port).await?;
+            Ok(TcpStream::connect(addr).await?)
+        }
+        #[cfg(not(feature = "dnssec"))]
+        {
+            Ok(TcpStream::connect(server_addr).await?)
+        }
+    }
+}
+
+impl<T, C, Tr> NetworkClient<T, C, Tr>
+    where
+        T: TypeTrait,
+        C: PartyClientTrait<T>,
+        Tr: Transport,
+{
+    /// Builds a `NetworkClient` around a carrier that is already connected — an in-memory duplex
+    /// pair handed out by a test, or a TLS stream whose handshake already completed — instead of
+    /// dialing a `server_addr` over TCP. Still runs the handshake over the given carrier.
+    pub(crate) async fn new_with_transport(client: C, mut stream: Tr, server_static_public: &PublicKey) -> Result<Self, crate::error::Error> {
+        let session = client_handshake(&mut stream, server_static_public).await?;
+        Ok(Self {
+            client,
+            stream,
+            tx_channel: SecureChannel::new(&session.tx_key),
+            rx_channel: SecureChannel::new(&session.rx_key),
+            parent: None,
+            children: Vec::new(),
+            phantom: Default::default(),
+        })
     }
 
     pub async fn run_protocol(&mut self) -> Result<T, Box<dyn Error>> {
         loop {
             let [lt, gt] = self.client.local_computation();
             let msg = ClientMessage::MsgCiphertext(lt, gt);
-            let msg_bytes = bincode::serialize(&msg).unwrap();
+            let msg_bytes = codec::encode_client_message(&msg)?;
             self.send_data_to_server(msg_bytes.as_slice()).await?;
 
 
             let data = self.receive_data_from_server().await?;
-            let msg = bincode::deserialize::<ServerMessage>(&data).unwrap();
+            let msg = codec::decode_server_message(&data)?;
             match msg {
                 ServerMessage::MsgDecryptRequest(sum_lt_enc, sum_gt_enc) => {
                     let [sum_lt, sum_gt] = self.client.compute_shares(sum_lt_enc, sum_gt_enc);
                     let msg = ClientMessage::MsgPartialDecryption(sum_lt, sum_gt);
-                    let msg_bytes = bincode::serialize(&msg).unwrap();
+                    let msg_bytes = codec::encode_client_message(&msg)?;
                     self.send_data_to_server(msg_bytes.as_slice()).await?;
                 }
+                // The server can send `Abort` here too: it aborts the round as soon as any
+                // party's ciphertext goes missing, which races ahead of the other honest
+                // parties still sitting at this first `receive_data_from_server`.
+                ServerMessage::MsgUpdateSearchRange(UpdateSearchRange::Abort) => {
+                    return Ok(T::from(-1)); //FIXME
+                }
                 _ => {
                     panic!("Unexpected message type");
                 }
             }
 
             let data = self.receive_data_from_server().await?;
-            let msg = bincode::deserialize::<ServerMessage>(&data).unwrap();
+            let msg = codec::decode_server_message(&data)?;
             match msg {
                 ServerMessage::MsgUpdateSearchRange(update) => {
                     match update {
@@ -92,21 +192,18 @@ impl<T, C> NetworkClient<T, C>
         Err(Box::new(std::io::Error::new(std::io::ErrorKind::Other, "Protocol failed")))
     }
 
-    // Send the given data to the server over the TCP connection
+    // Encrypt the given data and send it to the server as a single length-prefixed frame
     async fn send_data_to_server(&mut self, data: &[u8]) -> Result<(), Box<dyn Error>> {
-        // Write the data to the TCP stream, sending it to the server
-        self.stream.write_all(data).await?;
+        let frame = self.tx_channel.encrypt_frame(data).map_err(crate::error::Error::from)?;
+        write_frame(&mut self.stream, &frame).await.map_err(crate::error::Error::from)?;
         Ok(())
     }
 
-    // Receive data from the server over the TCP connection
+    // Read one length-prefixed frame from the server, looping until it is fully received, then
+    // decrypt it
     async fn receive_data_from_server(&mut self) -> Result<Vec<u8>, Box<dyn Error>> {
-        let mut buf = vec![0u8; 1024];
-        let mut reader = BufReader::new(&mut self.stream);
-        let n = reader.read(&mut buf).await.unwrap();
-        // If less than 1024 bytes were read, resize the buffer to the actual amount read
-        buf.truncate(n);
-
+        let buf = read_frame(&mut self.stream).await.map_err(crate::error::Error::from)?;
+        let buf = self.rx_channel.decrypt_frame(&buf).map_err(crate::error::Error::from)?;
         tracing::debug!("Received {} bytes from Server", buf.len());
 
         // Return the received data
@@ -1,17 +1,23 @@
-use std::error::Error;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use futures::future::join_all;
 use pht_crypto::{Ciphertext, paillier::PartialDecryption};
 use serde::{Deserialize, Serialize};
-use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::mpsc;
 use tokio::sync::Mutex;
+use tokio::time::timeout;
 
+use crate::error::Error;
+use crate::net::client_pool::{ClientConn, ClientPool};
+use crate::net::codec;
+use crate::net::framing::{read_frame, write_frame};
+use crate::net::handshake::{server_handshake, StaticKeyPair};
 use crate::net::netclient::ClientMessage;
-use crate::net::netclient::parse_client_message;
+use crate::net::secure_channel::SecureChannel;
+use crate::net::topology::AggregationTopology;
+use crate::net::transport::{Channel, Transport};
 use crate::party::party_server::PartyServerTrait;
 use crate::party::UpdateSearchRange;
 
@@ -21,48 +27,105 @@ pub(crate) enum ServerMessage {
     MsgUpdateSearchRange(UpdateSearchRange),
 }
 
-pub(crate) struct NetworkServer<S> where S: PartyServerTrait {
+// How long a client gets to answer within a single protocol round before it is marked dead.
+const ROUND_TIMEOUT: Duration = Duration::from_secs(30);
+// How long init_connections waits for all clients to connect before giving up.
+const BIND_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub(crate) struct NetworkServer<S, C = SecureChannel, T = TcpStream> where S: PartyServerTrait, C: Channel, T: Transport {
     server: S,
-    listener: TcpListener,
-    clients: Vec<Arc<Mutex<TcpStream>>>,
+    // `None` once every party has been handed an already-connected carrier via
+    // `from_transports`; only the `new`/`init_connections` TCP path needs a listener to accept
+    // from.
+    listener: Option<TcpListener>,
+    clients: ClientPool<C, T>,
     num_clients: usize,
+    // This server's long-term identity key. Each connecting party runs `server_handshake`
+    // against it to authenticate the server and derive that party's own session keys, replacing
+    // the pre-shared per-client keys `NetworkServer::new` used to require up front.
+    server_static: StaticKeyPair,
+    // How the connected parties fold their ciphertexts on the way up to this server. In `Tree`
+    // mode only the root layer (the parties whose `topology.parent_of` is `None`) would connect
+    // here directly; interior parties relay a pre-combined ciphertext instead of their own raw
+    // value. Actually driving that relaying through `NetworkClient` still needs to happen; for
+    // now this field just lets the server read off the strategy its `PartyServerTrait` was
+    // configured with.
+    topology: AggregationTopology,
 }
 
-impl<S> NetworkServer<S>
+impl<S, C> NetworkServer<S, C, TcpStream>
     where
         S: PartyServerTrait,
+        C: Channel,
 {
-    // Constructor to create a new NetworkServer instance
-    pub async fn new(address: &str, server: S, num_clients: usize) -> Result<Self, Box<dyn Error>>
+    // Constructor to create a new NetworkServer instance. `threshold` is the minimum number of
+    // live clients required to reconstruct a threshold-Paillier decryption; once fewer remain,
+    // the running round is aborted rather than left to hang. `server_static` is this server's
+    // long-term identity key; its public half needs to reach every party ahead of time (e.g.
+    // alongside `address`) so `NetworkClient`'s handshake can authenticate this server.
+    pub async fn new(address: &str, server: S, num_clients: usize, threshold: usize, server_static: StaticKeyPair) -> Result<Self, Box<dyn std::error::Error>>
         where S: PartyServerTrait {
         // Bind a TCP listener to the specified address to accept incoming connections
         let listener = TcpListener::bind(address).await?;
-        let clients: Vec<Arc<Mutex<TcpStream>>> = Vec::new();
+        let clients = ClientPool::new(threshold);
+        let topology = server.topology();
         // Return the constructed NetworkServer instance
-        Ok(Self { server, listener, clients, num_clients })
+        Ok(Self { server, listener: Some(listener), clients, num_clients, server_static, topology })
     }
 
-    // Main loop to accept incoming client connections
-    pub async fn init_connections(&mut self) -> Result<(), Box<dyn Error>> {
-        loop {
-            // Accept a new client connection, getting a TcpStream for the client
-            let (socket, _) = self.listener.accept().await?;
-
-            // Lock the mutex around the client list, getting a mutable reference to the vector
-            // let mut clients_guard = self.clients.lock().await;
-            // Add the new client to the vector inside self.clients
-            self.clients.push(Arc::from(Mutex::from(socket)));
-
-            if self.clients.len() == self.num_clients {
-                // If the number of clients has reached the expected number, then we can start the protocol
-                break;
+    // Main loop to accept incoming client connections, enforcing a deadline on the whole
+    // bind phase so a missing party does not block startup forever.
+    pub async fn init_connections(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let listener = self.listener.as_ref().expect("init_connections is only used with NetworkServer::new's TCP listener");
+        timeout(BIND_TIMEOUT, async {
+            loop {
+                // Accept a new client connection, getting a TcpStream for the client
+                let (mut socket, _) = listener.accept().await?;
+
+                let party_id = self.clients.len();
+                let session = server_handshake(&mut socket, &self.server_static).await?;
+                self.clients.push(ClientConn::new(socket, party_id, &session.tx_key, &session.rx_key));
+
+                if self.clients.len() == self.num_clients {
+                    // If the number of clients has reached the expected number, then we can start the protocol
+                    break;
+                }
             }
-        }
-        tracing::trace!("All clients connected");
+            tracing::trace!("All clients connected");
+            Ok::<(), Box<dyn std::error::Error>>(())
+        }).await.map_err(|_| "Timed out waiting for all clients to connect")??;
         Ok(())
     }
+}
+
+impl<S, C, T> NetworkServer<S, C, T>
+    where
+        S: PartyServerTrait,
+        C: Channel,
+        T: Transport,
+{
+    /// Builds a `NetworkServer` around carriers that are already connected — an in-memory duplex
+    /// pair handed out by a test, or a TLS stream whose handshake already completed — instead of
+    /// accepting them off a `TcpListener`. Runs `server_handshake` over each transport to derive
+    /// its session keys, then skips `init_connections` entirely since there is nothing left to
+    /// accept.
+    pub(crate) async fn from_transports(server: S, transports: Vec<T>, server_static: StaticKeyPair, threshold: usize) -> Result<Self, Error> {
+        let num_clients = transports.len();
+        let topology = server.topology();
+        let mut clients = ClientPool::new(threshold);
+        for (party_id, mut stream) in transports.into_iter().enumerate() {
+            let session = server_handshake(&mut stream, &server_static).await?;
+            clients.push(ClientConn::new(stream, party_id, &session.tx_key, &session.rx_key));
+        }
+        Ok(Self { server, listener: None, clients, num_clients, server_static, topology })
+    }
+
+    /// The aggregation topology this server was configured with (see `net::topology`).
+    pub(crate) fn topology(&self) -> AggregationTopology {
+        self.topology
+    }
 
-    pub(crate) async fn run_protocol(&mut self) {
+    pub(crate) async fn run_protocol(&mut self) -> Result<(), Error> {
         loop {
             // Initialize a vector of Ciphertext with the desired size.
             let mut lt_array_cipher: Vec<Ciphertext> = vec![Ciphertext::from(0); self.num_clients];
@@ -78,34 +141,15 @@ impl<S> NetworkServer<S>
                 let tx = tx.clone();  // Clone the transmitter for each client
 
                 tokio::spawn(async move {
-                    let mut client = client.lock().await;
-                    let mut reader = BufReader::new(&mut *client);
-
-                    // Read the data into a buffer of 1024 bytes
-                    let mut buf = vec![0u8; 128];
-                    let n = reader.read(&mut buf).await.unwrap();
-                    buf.truncate(n);
-
-                    tracing::trace!("Received {} bytes from Client (MsgCiphertext)", buf.len());
-
-                    let parsed_message = parse_client_message(&buf).unwrap();
-                    match parsed_message {
-                        ClientMessage::MsgCiphertext(ciphertext1, ciphertext2) => {
-                            tx.send((id, ciphertext1, ciphertext2)).await.expect("Failed to send");
-                        }
-                        _ => {
-                            panic!("Unexpected message type");
-                        }
-                    }
+                    let result = Self::receive_ciphertext(&client).await;
+                    tx.send((id, result)).await.expect("Failed to send");
                 })
             }).collect();
 
-            for _ in 0..self.num_clients {
-                if let Some((id, ciphertext1, ciphertext2)) = rx.recv().await {
-                    lt_array_cipher[id] = ciphertext1;
-                    gt_array_cipher[id] = ciphertext2;
-                }
-            }
+            let round_error = self.collect_round::<(Ciphertext, Ciphertext)>(&mut rx, &handles, |id, (ciphertext1, ciphertext2)| {
+                lt_array_cipher[id] = ciphertext1;
+                gt_array_cipher[id] = ciphertext2;
+            }, true).await;
 
             join_all(handles).await;
 
@@ -115,19 +159,27 @@ impl<S> NetworkServer<S>
 
             tracing::debug!("Total duration for reading Ciphertexts: {:?}", duration);
 
+            if let Some(e) = round_error {
+                self.abort_round().await?;
+                return Err(e);
+            }
+
             // You can now safely read from lt_array and gt_array
             let [sum_lt_enc, sum_gt_enc] = self.server.add_ciphertexts(&lt_array_cipher, &gt_array_cipher);
 
             // Broadcast the sum to all clients
             let msg = ServerMessage::MsgDecryptRequest(sum_lt_enc, sum_gt_enc);
-            let msg_bytes = bincode::serialize(&msg).unwrap();
-            self.broadcast_to_all_parties(msg_bytes.as_slice()).await.unwrap();
+            let msg_bytes = codec::encode_server_message(&msg)?;
+            self.broadcast_to_all_parties(msg_bytes.as_slice()).await?;
 
             // NEXT STATE!!!!
 
-            // Initialize a vector of Option<PartialDecryption> with the desired size.
+            // Reconstruction only needs `threshold`-many partial decryptions, not all n, so
+            // these grow as results arrive rather than being indexed by party id; `contributing`
+            // records which party each entry came from for `combine_shares`.
             let mut lt_array_decrypt: Vec<PartialDecryption> = Vec::new();
             let mut gt_array_decrypt: Vec<PartialDecryption> = Vec::new();
+            let mut contributing: Vec<usize> = Vec::new();
 
             // Get the timestamp before spawning tasks
             let start_time = Instant::now();
@@ -138,34 +190,16 @@ impl<S> NetworkServer<S>
                 let tx = tx.clone();  // Clone the transmitter for each client
 
                 tokio::spawn(async move {
-                    let mut client = client.lock().await;
-                    let mut reader = BufReader::new(&mut *client);
-
-                    // Read the data into a buffer of 1024 bytes
-                    let mut buf = vec![0u8; 128];
-                    let n = reader.read(&mut buf).await.unwrap();
-                    buf.truncate(n);
-
-                    tracing::debug!("Received {} bytes from Client (MsgPartialDecryption)", buf.len());
-
-                    let parsed_message = parse_client_message(&buf).unwrap();
-                    match parsed_message {
-                        ClientMessage::MsgPartialDecryption(decryption1, decryption2) => {
-                            tx.send((id, decryption1, decryption2)).await.expect("Failed to send");
-                        },
-                        _ => {
-                            panic!("Unexpected message type");
-                        }
-                    }
+                    let result = Self::receive_partial_decryption(&client).await;
+                    tx.send((id, result)).await.expect("Failed to send");
                 })
             }).collect();
 
-            for _ in 0..self.num_clients {
-                if let Some((id, decryption1, decryption2)) = rx.recv().await {
-                    lt_array_decrypt.push(decryption1);
-                    gt_array_decrypt.push(decryption2);
-                }
-            }
+            let round_error = self.collect_round::<(PartialDecryption, PartialDecryption)>(&mut rx, &handles, |id, (decryption1, decryption2)| {
+                lt_array_decrypt.push(decryption1);
+                gt_array_decrypt.push(decryption2);
+                contributing.push(id);
+            }, false).await;
 
             join_all(handles).await;
 
@@ -175,13 +209,27 @@ impl<S> NetworkServer<S>
 
             tracing::debug!("Total duration for reading Decrypted data: {:?}", duration);
 
+            if let Some(e) = round_error {
+                self.abort_round().await?;
+                return Err(e);
+            }
 
-            let sums = self.server.combine_shares(&lt_array_decrypt, &gt_array_decrypt);
+            let (sums, inconsistent) = match self.server.combine_shares(&lt_array_decrypt, &gt_array_decrypt, &contributing) {
+                Ok(result) => result,
+                Err(e) => {
+                    self.abort_round().await?;
+                    return Err(e);
+                }
+            };
+            for party in inconsistent {
+                tracing::warn!("Party {} submitted a partial decryption inconsistent with the rest of the group", party);
+                self.clients.mark_dead(party).await;
+            }
             let update = self.server.calculate_update(sums);
 
             let msg = ServerMessage::MsgUpdateSearchRange(update);
-            let msg_bytes = bincode::serialize(&msg).unwrap();
-            self.broadcast_to_all_parties(msg_bytes.as_slice()).await.unwrap();
+            let msg_bytes = codec::encode_server_message(&msg)?;
+            self.broadcast_to_all_parties(msg_bytes.as_slice()).await?;
 
             match update {
                 UpdateSearchRange::FoundK => {
@@ -197,19 +245,123 @@ impl<S> NetworkServer<S>
                 }
             }
         }
+        Ok(())
+    }
+
+    /// Drains `num_clients` results off `rx`, calling `on_success` for each one that arrived in
+    /// time and marking a client dead in the pool when it times out or errors. Returns the error
+    /// that aborts the round, if any.
+    ///
+    /// `require_all` controls how a dropout this round is judged: the ciphertext phase needs
+    /// every party's input to sum the counts correctly, so it sets this and any missing response
+    /// aborts regardless of how many parties the pool still considers live; the decryption phase
+    /// only needs `threshold`-many shares, so it leaves this unset and instead aborts only once
+    /// the pool's live count itself drops below that threshold.
+    ///
+    /// A client that stays connected but never answers (rather than erroring out) leaves no id
+    /// behind for a round-level `rx.recv()` timeout to act on, so it would otherwise dodge both
+    /// `mark_dead` and the `join_all` this round's caller still has to perform on `handles`.
+    /// `handles` lets this also abort that party's still-blocked `read_frame` task directly, so
+    /// the caller's `join_all` afterward returns promptly instead of hanging on it.
+    async fn collect_round<T>(
+        &self,
+        rx: &mut mpsc::Receiver<(usize, Result<T, Error>)>,
+        handles: &[tokio::task::JoinHandle<()>],
+        mut on_success: impl FnMut(usize, T),
+        require_all: bool,
+    ) -> Option<Error> {
+        let mut round_error = None;
+        let mut received = 0;
+        let mut responded = vec![false; self.num_clients];
+        for _ in 0..self.num_clients {
+            match timeout(ROUND_TIMEOUT, rx.recv()).await {
+                Ok(Some((id, result))) => {
+                    responded[id] = true;
+                    match result {
+                        Ok(value) => {
+                            self.clients.mark_seen(id).await;
+                            on_success(id, value);
+                            received += 1;
+                        }
+                        Err(e) => {
+                            tracing::warn!("Client {} failed during round: {}", id, e);
+                            self.clients.mark_dead(id).await;
+                            // In the decryption phase a failed party only costs its own share;
+                            // the threshold check below decides whether the round survives it.
+                            if require_all {
+                                round_error.get_or_insert(e);
+                            }
+                        }
+                    }
+                }
+                Ok(None) => {}
+                Err(_) => {
+                    tracing::warn!("Timed out waiting for a client response this round");
+                }
+            }
+        }
+        // Any id that never showed up above is either stuck reading or never connected to begin
+        // with; its task would otherwise leave `join_all` hanging, so abort it here too.
+        for (id, handle) in handles.iter().enumerate() {
+            if !responded[id] {
+                self.clients.mark_dead(id).await;
+                handle.abort();
+            }
+        }
+        if round_error.is_none() {
+            if require_all && received < self.num_clients {
+                round_error = Some(Error::MissingCiphertexts { have: received, need: self.num_clients });
+            } else if self.clients.below_threshold().await {
+                round_error = Some(Error::ClientDisconnected);
+            }
+        }
+        round_error
+    }
+
+    async fn receive_ciphertext(client: &Arc<Mutex<ClientConn<C, T>>>) -> Result<(Ciphertext, Ciphertext), Error> {
+        let mut client = client.lock().await;
+        let buf = read_frame(&mut client.stream).await?;
+        let buf = client.rx_channel.decrypt_frame(&buf)?;
+
+        tracing::trace!("Received {} bytes from Client (MsgCiphertext)", buf.len());
+
+        match codec::decode_client_message(&buf)? {
+            ClientMessage::MsgCiphertext(ciphertext1, ciphertext2) => Ok((ciphertext1, ciphertext2)),
+            _ => Err(Error::UnexpectedMessage),
+        }
+    }
+
+    async fn receive_partial_decryption(client: &Arc<Mutex<ClientConn<C, T>>>) -> Result<(PartialDecryption, PartialDecryption), Error> {
+        let mut client = client.lock().await;
+        let buf = read_frame(&mut client.stream).await?;
+        let buf = client.rx_channel.decrypt_frame(&buf)?;
+
+        tracing::debug!("Received {} bytes from Client (MsgPartialDecryption)", buf.len());
+
+        match codec::decode_client_message(&buf)? {
+            ClientMessage::MsgPartialDecryption(decryption1, decryption2) => Ok((decryption1, decryption2)),
+            _ => Err(Error::UnexpectedMessage),
+        }
+    }
+
+    /// Broadcasts `UpdateSearchRange::Abort` to the remaining clients so an honest party does
+    /// not stay blocked waiting on a round that can no longer complete.
+    async fn abort_round(&mut self) -> Result<(), Error> {
+        let msg = ServerMessage::MsgUpdateSearchRange(UpdateSearchRange::Abort);
+        let msg_bytes = codec::encode_server_message(&msg)?;
+        self.broadcast_to_all_parties(msg_bytes.as_slice()).await
     }
 
     //Broadcast the given data to all connected clients
-    async fn broadcast_to_all_parties(&mut self, data: &[u8]) -> Result<(), Box<dyn std::error::Error + Send>> {
+    async fn broadcast_to_all_parties(&mut self, data: &[u8]) -> Result<(), Error> {
         // Use futures::future::join_all to run all send operations in parallel
         let send_futures: Vec<_> = self.clients.iter().cloned().map(|client| {
             let data = data.to_vec();  // Clone the data for each client
             tokio::spawn(async move {
                 let mut locked_client = client.lock().await;
-                let mut writer = BufWriter::new(&mut *locked_client);
-                writer.write_all(&data).await.map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
-                writer.flush().await.map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
-                Ok::<(), Box<dyn std::error::Error + Send>>(())
+                let frame = locked_client.tx_channel.encrypt_frame(&data)?;
+                write_frame(&mut locked_client.stream, &frame).await?;
+                Ok::<(), Error>(())
             })
         }).collect();
 
@@ -220,7 +372,7 @@ impl<S> NetworkServer<S>
             match result {
                 Ok(Ok(_)) => {}, // Successful send
                 Ok(Err(e)) => return Err(e),
-                Err(e) => return Err(Box::new(e)), // Join error (panic in task)
+                Err(_) => return Err(Error::ClientDisconnected), // Join error (panic in task)
             }
         }
 
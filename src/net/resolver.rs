@@ -0,0 +1,44 @@
+//! DNSSEC-validated hostname resolution for `NetworkClient::new`, gated behind the `dnssec`
+//! feature. Off by default since it pulls in `trust-dns-resolver` and a DNSSEC-capable upstream
+//! resolver; enabling it means a party can be reached by hostname without trusting whatever the
+//! OS stub resolver hands back, which would otherwise let a spoofed DNS answer steer a party at
+//! a malicious aggregator before the X25519 handshake ever gets a chance to authenticate it.
+#![cfg(feature = "dnssec")]
+
+use std::net::SocketAddr;
+
+use trust_dns_resolver::config::{ResolverConfig, ResolverOpts};
+use trust_dns_resolver::TokioAsyncResolver;
+
+use crate::error::Error;
+
+/// Resolves hostnames through a DNSSEC-validating lookup, erroring instead of returning an
+/// address whenever the chain of trust back to the root doesn't validate. Holds its resolver
+/// handle so repeated lookups (e.g. across reconnects) don't redo resolver setup each time.
+pub(crate) struct DnssecResolver {
+    resolver: TokioAsyncResolver,
+}
+
+impl DnssecResolver {
+    pub(crate) fn new() -> Result<Self, Error> {
+        let mut opts = ResolverOpts::default();
+        opts.validate = true;
+        let resolver = TokioAsyncResolver::tokio(ResolverConfig::cloudflare(), opts);
+        Ok(Self { resolver })
+    }
+
+    /// Resolves `host` to a `SocketAddr` on `port`, taking the first DNSSEC-validated address
+    /// returned.
+    pub(crate) async fn resolve(&self, host: &str, port: u16) -> Result<SocketAddr, Error> {
+        let response = self.resolver.lookup_ip(host).await.map_err(|e| {
+            Error::Io(std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
+        })?;
+        let ip = response.iter().next().ok_or_else(|| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no DNSSEC-validated address found for {host}"),
+            ))
+        })?;
+        Ok(SocketAddr::new(ip, port))
+    }
+}
@@ -0,0 +1,76 @@
+use chacha20poly1305::{ChaCha20Poly1305, KeyInit, Nonce};
+use chacha20poly1305::aead::Aead;
+
+/// Size in bytes of the symmetric key used for the per-party transport channel.
+pub const KEY_SIZE: usize = 32;
+/// Size in bytes of the Poly1305 authentication tag prepended to every ciphertext frame.
+pub const TAG_SIZE: usize = 16;
+
+/// A monotonically increasing 96-bit nonce for one direction of a channel.
+///
+/// ChaCha20-Poly1305 nonces must never repeat for a given key, so we keep a simple little-endian
+/// counter in the low 8 bytes and zero-pad the remaining 4 high bytes.
+#[derive(Debug, Default)]
+pub(crate) struct NonceCounter(u64);
+
+impl NonceCounter {
+    /// Returns the current nonce and advances the counter for the next frame.
+    fn next(&mut self) -> Nonce {
+        let mut bytes = [0u8; 12];
+        bytes[..8].copy_from_slice(&self.0.to_le_bytes());
+        self.0 += 1;
+        *Nonce::from_slice(&bytes)
+    }
+}
+
+/// An authenticated, encrypted channel over a single direction, keyed with one of the two
+/// per-direction 32-byte session keys that `net::handshake` derives for each connection.
+///
+/// Every party keeps one `SecureChannel` for sending and one for receiving so that the two
+/// directions never share a nonce counter.
+pub(crate) struct SecureChannel {
+    cipher: ChaCha20Poly1305,
+    nonce_counter: NonceCounter,
+}
+
+impl SecureChannel {
+    pub(crate) fn new(key: &[u8; KEY_SIZE]) -> Self {
+        Self {
+            cipher: ChaCha20Poly1305::new(key.into()),
+            nonce_counter: NonceCounter::default(),
+        }
+    }
+
+    /// Encrypts `plaintext` into a single frame: the Poly1305 tag followed by the ciphertext.
+    pub(crate) fn encrypt_frame(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, SecureChannelError> {
+        let nonce = self.nonce_counter.next();
+        let mut ciphertext = self.cipher.encrypt(&nonce, plaintext)
+            .map_err(|_| SecureChannelError::Encryption)?;
+        // `Aead::encrypt` already appends the tag at the end; move it to the front so the wire
+        // format matches "16-byte tag, then ciphertext" regardless of the underlying crate layout.
+        let tag = ciphertext.split_off(ciphertext.len() - TAG_SIZE);
+        let mut frame = tag;
+        frame.extend_from_slice(&ciphertext);
+        Ok(frame)
+    }
+
+    /// Decrypts a frame produced by `encrypt_frame`, rejecting it on tag-verification failure.
+    pub(crate) fn decrypt_frame(&mut self, frame: &[u8]) -> Result<Vec<u8>, SecureChannelError> {
+        if frame.len() < TAG_SIZE {
+            return Err(SecureChannelError::TagVerification);
+        }
+        let (tag, ciphertext) = frame.split_at(TAG_SIZE);
+        let mut reassembled = Vec::with_capacity(ciphertext.len() + TAG_SIZE);
+        reassembled.extend_from_slice(ciphertext);
+        reassembled.extend_from_slice(tag);
+        let nonce = self.nonce_counter.next();
+        self.cipher.decrypt(&nonce, reassembled.as_slice())
+            .map_err(|_| SecureChannelError::TagVerification)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum SecureChannelError {
+    Encryption,
+    TagVerification,
+}
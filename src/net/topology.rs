@@ -0,0 +1,58 @@
+//! Aggregation topology: how the `lt`/`gt` ciphertexts *would* travel from the parties up to the
+//! server, if relaying were wired up.
+//!
+//! `Flat` is today's star topology, where every party sends its ciphertexts directly to the
+//! server and `PartyServer::add_ciphertexts` folds all of them at once — this is the only
+//! topology actually exercised on the wire right now. `Tree` is this module's model of a balanced
+//! tree below the server where each interior node would fold its own children's ciphertexts with
+//! [`pht_crypto::paillier::PublicKey::add_encrypted`] before forwarding a single combined
+//! ciphertext upward, cutting the messages the server itself has to handle from O(n) to
+//! O(fan_out). That forwarding is not implemented: `NetworkClient` has no listener to accept a
+//! child's connection on, so every party still dials `server_addr` directly regardless of its
+//! computed `parent`/`children` (see `NetworkClient::new_with_topology`). This module only
+//! supplies the layer math (`parent_of`/`children_of`) a future relay would need.
+//!
+//! Party ids are 0-indexed and laid out layer by layer: layer 1 holds the first `fan_out`
+//! parties (children of the server/root), layer 2 holds the next `fan_out^2`, and so on. That id
+//! need not match a party's position in `databases` or any other natural index — see
+//! [`super::gossip`] for computing a stake-weighted layer assignment out of gossiped membership
+//! (also not wired to any actual gossip exchange yet; see that module's docs).
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AggregationTopology {
+    /// Every party sends its ciphertexts directly to the server.
+    Flat,
+    /// Parties are arranged into a balanced tree with the given fan-out below the server.
+    Tree { fan_out: usize },
+}
+
+impl AggregationTopology {
+    /// The id of `idx`'s parent, or `None` if `idx` is a root child that reports directly to the
+    /// server (always the case for `Flat`).
+    pub(crate) fn parent_of(&self, idx: usize) -> Option<usize> {
+        match self {
+            AggregationTopology::Flat => None,
+            AggregationTopology::Tree { fan_out } => {
+                if idx < *fan_out {
+                    None
+                } else {
+                    Some(idx / fan_out - 1)
+                }
+            }
+        }
+    }
+
+    /// The ids of `idx`'s children, i.e. the parties whose combined ciphertext `idx` must fold in
+    /// before forwarding to its own parent (or the server, if `idx` is a root child).
+    pub(crate) fn children_of(&self, idx: usize, n: usize) -> Vec<usize> {
+        match self {
+            AggregationTopology::Flat => Vec::new(),
+            AggregationTopology::Tree { fan_out } => {
+                let first = (idx + 1) * fan_out;
+                (first..(first + fan_out).min(n)).collect()
+            }
+        }
+    }
+}
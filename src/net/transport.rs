@@ -0,0 +1,86 @@
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use crate::error::Error;
+use crate::net::secure_channel::{SecureChannel, KEY_SIZE};
+
+/// Abstracts the per-direction byte transform applied to every frame `NetworkServer`/
+/// `NetworkClient` exchange, decoupling the MPC state machine from the concrete transport.
+///
+/// `SecureChannel` is the production implementation (ChaCha20-Poly1305 AEAD); `NullCipher` is a
+/// plaintext passthrough used so in-process tests can drive the networking code without paying
+/// for real encryption or needing real sockets.
+pub(crate) trait Channel: Send {
+    fn new(key: &[u8; KEY_SIZE]) -> Self where Self: Sized;
+
+    fn encrypt_frame(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, Error>;
+
+    fn decrypt_frame(&mut self, frame: &[u8]) -> Result<Vec<u8>, Error>;
+}
+
+impl Channel for SecureChannel {
+    fn new(key: &[u8; KEY_SIZE]) -> Self {
+        SecureChannel::new(key)
+    }
+
+    fn encrypt_frame(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(SecureChannel::encrypt_frame(self, plaintext)?)
+    }
+
+    fn decrypt_frame(&mut self, frame: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(SecureChannel::decrypt_frame(self, frame)?)
+    }
+}
+
+/// A `Channel` that does not encrypt or authenticate anything; frames pass through unchanged.
+/// Used to keep the null-transport path testable in-process, and as a stepping stone before a
+/// real AEAD handshake is negotiated.
+pub(crate) struct NullCipher;
+
+impl Channel for NullCipher {
+    fn new(_key: &[u8; KEY_SIZE]) -> Self {
+        NullCipher
+    }
+
+    fn encrypt_frame(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(plaintext.to_vec())
+    }
+
+    fn decrypt_frame(&mut self, frame: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(frame.to_vec())
+    }
+}
+
+/// The concrete carrier frames travel over, independent of `Channel`'s encryption and
+/// `net::framing`'s length-prefixing. `read_frame`/`write_frame` only need `AsyncRead`/
+/// `AsyncWrite`, so any carrier that provides them already qualifies; this trait exists purely to
+/// give that bound a name `ClientConn`/`NetworkServer`/`NetworkClient` can be generic over,
+/// mirroring how pluggable-transport frameworks let a protocol run unchanged over swappable
+/// carriers. A plain `TcpStream` is the default (and today's only deployed) carrier; `tokio::io`'s
+/// in-memory duplex pair lets tests drive the same protocol loop without binding real ports, and
+/// the `tls` feature adds a TLS-wrapped carrier for deployments that want transport
+/// confidentiality independent of (or layered under) `SecureChannel`'s AEAD framing.
+pub(crate) trait Transport: AsyncRead + AsyncWrite + Unpin + Send + 'static {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send + 'static> Transport for T {}
+
+/// An in-memory, full-duplex carrier with no socket behind it. `tokio::io::duplex` hands back a
+/// connected pair; give one end to the server side and one to the client side to run the real
+/// protocol loop between them without a `TcpListener`.
+pub(crate) type InMemoryTransport = tokio::io::DuplexStream;
+
+/// Creates a connected pair of in-memory carriers, buffering up to `buffer_size` bytes of
+/// unread data in each direction before a write blocks.
+pub(crate) fn in_memory_pair(buffer_size: usize) -> (InMemoryTransport, InMemoryTransport) {
+    tokio::io::duplex(buffer_size)
+}
+
+/// TLS-wrapped carrier, available behind the `tls` feature for deployments that want transport
+/// confidentiality and server authentication below the application layer (e.g. to terminate at a
+/// load balancer, or to hide frame sizes/timing from a network observer watching the raw AEAD
+/// frames `SecureChannel` produces). Thin by design: it is just `tokio_rustls::TlsStream` renamed
+/// so it reads naturally as one of the three `Transport` carriers alongside `TcpStream` and
+/// `InMemoryTransport`; the handshake itself happens wherever the stream is established (a
+/// `TlsConnector`/`TlsAcceptor` call ahead of `NetworkClient::new_with_transport` /
+/// `NetworkServer::from_transports`), not inside this type.
+#[cfg(feature = "tls")]
+pub(crate) type TlsTransport<IO> = tokio_rustls::TlsStream<IO>;
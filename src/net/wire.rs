@@ -0,0 +1,107 @@
+//! Protobuf wire encoding for `ServerMessage`/`ClientMessage`, gated behind the `protobuf`
+//! feature. Bincode (the default) pins every participant to Rust and to matching struct
+//! layouts; this schema gives a non-Rust party a protobuf envelope to speak the same protocol
+//! against `NetworkServer`. The ciphertext and partial-decryption fields inside it still carry
+//! bincode-serialized `pht_crypto` values, so such a party also has to decode that layout.
+#![cfg(feature = "protobuf")]
+
+use bincode::{deserialize, serialize};
+use pht_crypto::{Ciphertext, paillier::PartialDecryption};
+use prost::Message;
+
+use crate::error::Error;
+use crate::net::netclient::ClientMessage;
+use crate::net::netserver::ServerMessage;
+use crate::party::UpdateSearchRange;
+
+// Generated from proto/kre.proto by build.rs.
+pub(crate) mod generated {
+    include!(concat!(env!("OUT_DIR"), "/kre.rs"));
+}
+
+use generated as wire;
+
+/// Wraps a value behind bincode so the proto schema stays a stable, language-neutral envelope
+/// while the payload itself keeps using the same big-integer representation `pht_crypto`
+/// already serializes with `serde`.
+fn to_big_int_bytes<T: serde::Serialize>(value: &T) -> Result<wire::BigIntBytes, Error> {
+    Ok(wire::BigIntBytes { value: serialize(value)? })
+}
+
+fn from_big_int_bytes<T: serde::de::DeserializeOwned>(bytes: &wire::BigIntBytes) -> Result<T, Error> {
+    Ok(deserialize(&bytes.value)?)
+}
+
+pub(crate) fn encode_server_message(msg: &ServerMessage) -> Result<Vec<u8>, Error> {
+    let payload = match msg {
+        ServerMessage::MsgDecryptRequest(lt, gt) => {
+            wire::server_message::Payload::DecryptRequest(wire::DecryptRequest {
+                lt_ciphertext: Some(to_big_int_bytes(lt)?),
+                gt_ciphertext: Some(to_big_int_bytes(gt)?),
+            })
+        }
+        ServerMessage::MsgUpdateSearchRange(update) => {
+            wire::server_message::Payload::UpdateSearchRange(match update {
+                UpdateSearchRange::FoundK => wire::SearchRangeUpdate::FoundK as i32,
+                UpdateSearchRange::SearchBelow => wire::SearchRangeUpdate::SearchBelow as i32,
+                UpdateSearchRange::SearchAbove => wire::SearchRangeUpdate::SearchAbove as i32,
+                UpdateSearchRange::Abort => wire::SearchRangeUpdate::Abort as i32,
+            })
+        }
+    };
+    Ok(wire::ServerMessage { payload: Some(payload) }.encode_to_vec())
+}
+
+pub(crate) fn decode_server_message(bytes: &[u8]) -> Result<ServerMessage, Error> {
+    let msg = wire::ServerMessage::decode(bytes).map_err(|_| Error::Decode(Box::new(bincode::ErrorKind::Custom("invalid protobuf frame".into()))))?;
+    match msg.payload.ok_or(Error::UnexpectedMessage)? {
+        wire::server_message::Payload::DecryptRequest(req) => {
+            let lt = from_big_int_bytes(&req.lt_ciphertext.ok_or(Error::UnexpectedMessage)?)?;
+            let gt = from_big_int_bytes(&req.gt_ciphertext.ok_or(Error::UnexpectedMessage)?)?;
+            Ok(ServerMessage::MsgDecryptRequest(lt, gt))
+        }
+        wire::server_message::Payload::UpdateSearchRange(update) => {
+            let update = match wire::SearchRangeUpdate::try_from(update).map_err(|_| Error::UnexpectedMessage)? {
+                wire::SearchRangeUpdate::FoundK => UpdateSearchRange::FoundK,
+                wire::SearchRangeUpdate::SearchBelow => UpdateSearchRange::SearchBelow,
+                wire::SearchRangeUpdate::SearchAbove => UpdateSearchRange::SearchAbove,
+                wire::SearchRangeUpdate::Abort => UpdateSearchRange::Abort,
+            };
+            Ok(ServerMessage::MsgUpdateSearchRange(update))
+        }
+    }
+}
+
+pub(crate) fn encode_client_message(msg: &ClientMessage) -> Result<Vec<u8>, Error> {
+    let payload = match msg {
+        ClientMessage::MsgCiphertext(lt, gt) => {
+            wire::client_message::Payload::Ciphertext(wire::Ciphertext {
+                lt: Some(to_big_int_bytes(lt)?),
+                gt: Some(to_big_int_bytes(gt)?),
+            })
+        }
+        ClientMessage::MsgPartialDecryption(lt, gt) => {
+            wire::client_message::Payload::PartialDecryption(wire::PartialDecryption {
+                lt: Some(to_big_int_bytes(lt)?),
+                gt: Some(to_big_int_bytes(gt)?),
+            })
+        }
+    };
+    Ok(wire::ClientMessage { payload: Some(payload) }.encode_to_vec())
+}
+
+pub(crate) fn decode_client_message(bytes: &[u8]) -> Result<ClientMessage, Error> {
+    let msg = wire::ClientMessage::decode(bytes).map_err(|_| Error::Decode(Box::new(bincode::ErrorKind::Custom("invalid protobuf frame".into()))))?;
+    match msg.payload.ok_or(Error::UnexpectedMessage)? {
+        wire::client_message::Payload::Ciphertext(c) => {
+            let lt: Ciphertext = from_big_int_bytes(&c.lt.ok_or(Error::UnexpectedMessage)?)?;
+            let gt: Ciphertext = from_big_int_bytes(&c.gt.ok_or(Error::UnexpectedMessage)?)?;
+            Ok(ClientMessage::MsgCiphertext(lt, gt))
+        }
+        wire::client_message::Payload::PartialDecryption(d) => {
+            let lt: PartialDecryption = from_big_int_bytes(&d.lt.ok_or(Error::UnexpectedMessage)?)?;
+            let gt: PartialDecryption = from_big_int_bytes(&d.gt.ok_or(Error::UnexpectedMessage)?)?;
+            Ok(ClientMessage::MsgPartialDecryption(lt, gt))
+        }
+    }
+}
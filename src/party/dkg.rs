@@ -0,0 +1,144 @@
+//! Verifiable dealer selection for the threshold Paillier key.
+//!
+//! Every party runs its own dealer round: it samples a degree-(t-1) polynomial over a freshly
+//! generated threshold-Paillier key, and commits to the share it owes each recipient. `deal`
+//! returns those shares unencrypted; this module does not encrypt them under any recipient's
+//! communication key, so their confidentiality rests on the channel they are delivered over.
+//! Recipients verify their
+//! share against the dealer's commitment and raise a [`Complaint`] against dealers that cheat.
+//! Once complaints are resolved, the parties settle on a single qualified dealer to source the
+//! joint key from.
+//!
+//! Despite the module name, this is **not** full distributed key generation: the winning dealer
+//! still single-handedly generated the joint key pair and so is still a trusted dealer for
+//! secrecy, exactly as `create_server_clients`'s fixed dealer was. What this buys over that is
+//! *accountability* — every party verifies its own share against a public commitment and can
+//! prove a dealer cheated, so the parties no longer have to trust a dealer picked in advance, and
+//! a cheating dealer is caught and disqualified instead of silently corrupting the key. Removing
+//! the trusted dealer for secrecy too would mean combining every qualified dealer's contribution
+//! into the joint key (e.g. additively, the way Feldman VSS combines per-dealer secrets in a
+//! discrete-log group), but `pht_crypto` exposes neither the group exponentiation a textbook
+//! Feldman commitment needs (`g^{coefficient}`) nor an operator to add several dealers'
+//! `PrivateKeyShare`/`PublicKey` values together — and, unlike Feldman VSS, each dealer here also
+//! generates its own Paillier modulus, so there isn't even a single shared group to combine
+//! shares in until the dealers jointly generate one biprime together, which is a substantially
+//! larger protocol in its own right. Commitments here are therefore SHA-256 hashes of each
+//! recipient's share (binding, but not homomorphic), and the parties deterministically pick the
+//! lowest-indexed qualified dealer as the key's source rather than summing contributions
+//! together. This remains a documented trade-off imposed by the crate boundary and the lack of a
+//! joint-biprime-generation step, not a protocol design choice.
+
+use std::collections::HashSet;
+
+use pht_crypto::paillier::{generate_key_pair, Polynomial, PrivateKeyShare, PublicKey};
+use rug::rand::RandState;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// A dealer's SHA-256 commitment to the share it owes each recipient, broadcast before any share
+/// is sent so recipients can detect a tampered or inconsistent share.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DealerCommitment {
+    pub(crate) dealer: u32,
+    // Indexed by recipient id.
+    pub(crate) share_commitments: Vec<[u8; 32]>,
+}
+
+/// One dealer's evaluation share for a single recipient, together with the `PublicKey` half of
+/// the threshold key pair that dealer generated.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DealerShare {
+    pub(crate) dealer: u32,
+    pub(crate) recipient: u32,
+    pub(crate) pk: PublicKey,
+    pub(crate) share: PrivateKeyShare,
+}
+
+/// Raised by `complainant` against `accused` when the share received from `accused` does not
+/// match the commitment `accused` broadcast.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Complaint {
+    pub(crate) complainant: u32,
+    pub(crate) accused: u32,
+}
+
+fn commit_share(share: &PrivateKeyShare) -> [u8; 32] {
+    let bytes = bincode::serialize(share).expect("PrivateKeyShare is always serializable");
+    let digest = Sha256::digest(&bytes);
+    digest.into()
+}
+
+/// Drives one party's side of the DKG: acting as a dealer for its own polynomial, and as a
+/// recipient verifying every other party's shares.
+pub struct DkgParty {
+    idx: u32,
+    n: u32,
+    t: u32,
+    disqualified: HashSet<u32>,
+}
+
+impl DkgParty {
+    pub fn new(idx: u32, n: u32, t: u32) -> Self {
+        Self { idx, n, t, disqualified: HashSet::new() }
+    }
+
+    /// Acts as a dealer: generates a fresh threshold key pair and polynomial, and returns the
+    /// commitment to broadcast plus the per-recipient shares to send over each recipient's
+    /// authenticated channel.
+    pub fn deal(&self, rand: &mut RandState<'static>) -> (DealerCommitment, Vec<DealerShare>) {
+        let (pk, sk) = generate_key_pair(128, self.n, self.t).expect("key generation parameters are valid");
+        let poly = Polynomial::new(&sk, rand);
+
+        let shares: Vec<DealerShare> = (0..self.n)
+            .map(|recipient| DealerShare {
+                dealer: self.idx,
+                recipient,
+                pk: pk.clone(),
+                share: poly.compute(recipient),
+            })
+            .collect();
+
+        let commitment = DealerCommitment {
+            dealer: self.idx,
+            share_commitments: shares.iter().map(|s| commit_share(&s.share)).collect(),
+        };
+
+        (commitment, shares)
+    }
+
+    /// Verifies a received share against the dealer's broadcast commitment, returning a
+    /// [`Complaint`] to raise against the dealer if it doesn't match.
+    pub fn verify_share(&self, commitment: &DealerCommitment, share: &DealerShare) -> Option<Complaint> {
+        let expected = commitment.share_commitments.get(share.recipient as usize)?;
+        if *expected != commit_share(&share.share) {
+            Some(Complaint { complainant: self.idx, accused: share.dealer })
+        } else {
+            None
+        }
+    }
+
+    /// Disqualifies a dealer once enough complaints have been raised against it (any valid
+    /// complaint is sufficient here, since a dealer that sent even one bad share cannot be
+    /// trusted to have shared the rest of its polynomial correctly either).
+    pub fn disqualify(&mut self, accused: u32) {
+        self.disqualified.insert(accused);
+    }
+
+    /// Finalizes the key once every dealer has either been disqualified or had its shares
+    /// verified: picks the lowest-indexed qualified dealer and returns its `PublicKey` together
+    /// with this party's `PrivateKeyShare` of it.
+    ///
+    /// The winning dealer, not the group, is the one who generated this key pair and so is the
+    /// only party who ever held the complete secret key — see the module docs for why qualified
+    /// dealers are selected among rather than combined.
+    ///
+    /// `all_shares` holds the shares this party received from every dealer (including its own),
+    /// indexed by dealer id.
+    pub fn finalize(&self, all_shares: &[DealerShare]) -> Option<(PublicKey, PrivateKeyShare)> {
+        let winner = all_shares
+            .iter()
+            .filter(|s| !self.disqualified.contains(&s.dealer))
+            .min_by_key(|s| s.dealer)?;
+        Some((winner.pk.clone(), winner.share.clone()))
+    }
+}
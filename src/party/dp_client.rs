@@ -64,10 +64,23 @@ pub fn get_scale_sigmoid(noise_level: NoiseLevel, result: usize, db_size: usize)
     scale
 }
 
+/// Which Laplace mechanism `DPClient::add_noise` draws from.
+#[derive(PartialEq, Copy, Clone)]
+pub enum NoiseMechanism {
+    /// Samples continuous Laplace noise and rounds it to the nearest integer. The rounding
+    /// biases the perturbed count, so this mode no longer gives a clean epsilon-DP guarantee
+    /// for integers; kept as the default since it's what every existing caller already expects.
+    Continuous,
+    /// Samples the discrete (two-sided geometric) Laplace distribution directly, so there is no
+    /// rounding step and the epsilon-DP guarantee holds exactly for integer counts.
+    Discrete,
+}
+
 pub struct DPClient<T> where T: TypeTrait {
     client: PartyClient<T>,
     noise_level: NoiseLevel,
     get_scale_fn: GetScaleFn,
+    mechanism: NoiseMechanism,
     pub noise_array: Vec<f64>,
 }
 
@@ -107,14 +120,26 @@ impl<T> PartyClientTrait<T> for DPClient<T> where T: TypeTrait {
     fn update_search_range(&mut self, update: UpdateSearchRange) -> Option<T> {
         self.client.update_search_range(update)
     }
+
+    fn owns_record(&self) -> Option<usize> {
+        self.client.owns_record()
+    }
 }
 
 impl<T> DPClient<T> where T: TypeTrait {
+    /// Creates a `DPClient` using the continuous Laplace mechanism, as every caller did before
+    /// the discrete mechanism existed. Use [`DPClient::new_with_mechanism`] to opt into
+    /// [`NoiseMechanism::Discrete`].
     pub fn new(client: PartyClient<T>, get_scale_fn: GetScaleFn, noise_level: NoiseLevel) -> Self {
+        Self::new_with_mechanism(client, get_scale_fn, noise_level, NoiseMechanism::Continuous)
+    }
+
+    pub fn new_with_mechanism(client: PartyClient<T>, get_scale_fn: GetScaleFn, noise_level: NoiseLevel, mechanism: NoiseMechanism) -> Self {
         DPClient {
             client,
             noise_level,
             get_scale_fn,
+            mechanism,
             noise_array: vec![],
         }
     }
@@ -131,9 +156,18 @@ impl<T> DPClient<T> where T: TypeTrait {
 
         let scale = (self.get_scale_fn)(self.noise_level, result, db_size);
 
-        let noise = laplace_point(&mut rng, scale);
-        self.noise_array.push(noise);
-        let noise = noise.round() as isize;
+        let noise = match self.mechanism {
+            NoiseMechanism::Continuous => {
+                let raw = laplace_point(&mut rng, scale);
+                self.noise_array.push(raw);
+                raw.round() as isize
+            }
+            NoiseMechanism::Discrete => {
+                let noise = discrete_laplace_point(&mut rng, scale);
+                self.noise_array.push(noise as f64);
+                noise
+            }
+        };
         ((result as isize) + noise).max(0) as usize
     }
 }
@@ -148,6 +182,28 @@ fn laplace_point<R: Rng>(rng: &mut R, scale: f64) -> f64 {
     laplace_point
 }
 
+/// Samples one draw from the discrete (two-sided geometric) Laplace distribution with scale
+/// `b`, i.e. `G1 - G2` for independent `G1, G2 ~ Geometric(p)` with `p = 1 - exp(-1/b)`;
+/// `add_noise` adds the result to the count.
+/// Unlike rounding a continuous Laplace sample, this has no rounding artifact: the difference of
+/// two geometrics is exactly discrete Laplace over the integers.
+fn discrete_laplace_point<R: Rng>(rng: &mut R, scale: f64) -> isize {
+    if scale <= 0.0 {
+        return 0;
+    }
+    let p = 1.0 - (-1.0 / scale).exp();
+    geometric_sample(rng, p) as isize - geometric_sample(rng, p) as isize
+}
+
+/// Samples `Geometric(p)` (supported on `0, 1, 2, ...`) via inverse CDF: `floor(ln(u) / ln(1-p))`
+/// for `u` uniform in `(0, 1)`. `Uniform::new(0.0, 1.0)` can draw exactly `0.0`, which would send
+/// `u.ln()` to `-inf` and saturate the cast to `u64::MAX`; drawing from the open interval instead
+/// (same fix as `gossip::weighted_shuffle`) keeps every draw finite.
+fn geometric_sample<R: Rng>(rng: &mut R, p: f64) -> u64 {
+    let u: f64 = rng.gen_range(f64::EPSILON..1.0);
+    (u.ln() / (1.0 - p).ln()).floor() as u64
+}
+
 // Computes the range for the laplacian noise given the result. Is currently not used.
 fn get_noise_range(result: usize) -> (f64, f64) {
     // Compute the range as being at most 10% of the result.
@@ -169,3 +225,16 @@ fn test_laplace_points() {
         print!("{} ", laplace_point);
     }
 }
+
+#[test]
+fn test_discrete_laplace_points() {
+    let mut rng = rand::thread_rng();
+    let db_size = 10;
+
+    for _ in 0..10000 {
+        let result = rng.gen_range(0..10);
+        let scale = get_scale_sigmoid(NoiseLevel::MEDIUM, result, db_size);
+        let discrete_laplace_point = discrete_laplace_point(&mut rng, scale);
+        print!("{} ", discrete_laplace_point);
+    }
+}
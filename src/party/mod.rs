@@ -7,6 +7,8 @@ use serde::{Deserialize, Serialize};
 pub mod party_client;
 pub mod party_server;
 pub mod dp_client;
+pub mod dkg;
+pub mod dpf;
 
 #[derive(Debug, Serialize, Deserialize, Copy, Clone)]
 pub enum UpdateSearchRange {
@@ -17,6 +17,13 @@ pub trait PartyClientTrait<T>: Send where T: TypeTrait {
     fn compute_shares(&self, lt: Ciphertext, gt: Ciphertext) -> [PartialDecryption; 2];
 
     fn update_search_range(&mut self, update: UpdateSearchRange) -> Option<T>;
+
+    /// The local index of this party's database that exactly matched the search midpoint on the
+    /// last `local_computation`, if any. Once the binary search has converged, `Some` for every
+    /// party holding an element equal to the winning value and `None` for the rest.
+    fn owns_record(&self) -> Option<usize> {
+        None
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -38,6 +45,8 @@ pub struct PartyClient<T> where T: TypeTrait {
     // However, this optimization does only work for min/max queries.
     pub(crate) search_range_idx: Range<usize>,
     pub(crate) greater_than_m_idx: Option<usize>,
+    // Local index of the element that exactly matched `m` on the last `local_comp1`, if any.
+    pub(crate) equal_to_m_idx: Option<usize>,
     // middle-point
     pub(crate) m: T,
     pub(crate) pk: PublicKey,
@@ -74,6 +83,10 @@ impl<T> PartyClientTrait<T> for PartyClient<T> where T: TypeTrait {
         [lt, gt]
     }
 
+    fn owns_record(&self) -> Option<usize> {
+        self.equal_to_m_idx
+    }
+
     fn update_search_range(&mut self, update: UpdateSearchRange) -> Option<T> {
         match update {
             UpdateSearchRange::FoundK => Some(self.m.clone()),
@@ -122,6 +135,7 @@ impl<T> PartyClient<T> where T: TypeTrait {
             key_share,
             rand,
             greater_than_m_idx: None,
+            equal_to_m_idx: None,
         }
     }
 
@@ -129,12 +143,12 @@ impl<T> PartyClient<T> where T: TypeTrait {
         self.m = self.search_range[0].average_floor(&self.search_range[1]);
         // If we are searching for the min/max, we can slice the database to a shorter search range.
         let range_idx = if self.k == 1 || self.k == self.databases_size { self.search_range_idx.clone() } else { 0..self.database.len() };
-        let ([less, greater], greater_than_m_idx) = self.database[range_idx]
+        let ([less, greater], greater_than_m_idx, equal_to_m_idx) = self.database[range_idx.clone()]
             .iter()
             .enumerate()
             .fold(
-                ([0, 0], None),
-                |([mut less, mut greater], mut greater_than_m_idx), (idx, el)| {
+                ([0, 0], None, None),
+                |([mut less, mut greater], mut greater_than_m_idx, mut equal_to_m_idx), (idx, el)| {
                     match el.cmp(&self.m) {
                         Ordering::Less => {
                             less += 1;
@@ -143,14 +157,17 @@ impl<T> PartyClient<T> where T: TypeTrait {
                             greater += 1;
                             greater_than_m_idx.get_or_insert(idx);
                         }
-                        Ordering::Equal => (),
+                        Ordering::Equal => {
+                            equal_to_m_idx.get_or_insert(idx);
+                        }
                     };
-                    ([less, greater], greater_than_m_idx)
+                    ([less, greater], greater_than_m_idx, equal_to_m_idx)
                 },
             );
 
         // Add search_range_idx.start because we sliced the database before the enumerate.
         self.greater_than_m_idx = greater_than_m_idx.map(|idx| idx + self.search_range_idx.start);
+        self.equal_to_m_idx = equal_to_m_idx.map(|idx| idx + range_idx.start);
         [less, greater]
     }
 
@@ -5,6 +5,8 @@ use pht_crypto::paillier::{PartialDecryption, PublicKey};
 
 use party::UpdateSearchRange;
 
+use crate::error::Error;
+use crate::net::topology::AggregationTopology;
 use crate::party;
 
 pub trait PartyServerTrait {
@@ -12,11 +14,27 @@ pub trait PartyServerTrait {
         &mut self, lt_ciphertexts: &[Ciphertext], gt_ciphertexts: &[Ciphertext],
     ) -> [Ciphertext; 2];
 
+    /// Reconstructs the plaintext sums from the partial decryptions that arrived in time.
+    /// `contributing` holds the party index each entry in `lt_shares`/`gt_shares` came from, in
+    /// the same order, so implementations can confirm at least the reconstruction threshold is
+    /// met before trusting `PublicKey::share_combine`'s Lagrange interpolation, and can cross-check
+    /// shares against each other when more than `threshold` arrived. Returns the reconstructed
+    /// sums together with the party indices whose shares were found inconsistent with the rest of
+    /// the group, so the caller can treat them like any other dropout.
     fn combine_shares(
-        &self, lt_shares: &[PartialDecryption], gt_shares: &[PartialDecryption],
-    ) -> [Plaintext; 2];
+        &self, lt_shares: &[PartialDecryption], gt_shares: &[PartialDecryption], contributing: &[usize],
+    ) -> Result<([Plaintext; 2], Vec<usize>), Error>;
 
     fn calculate_update(&self, plaintexts: [Plaintext; 2]) -> UpdateSearchRange;
+
+    /// The aggregation strategy this server expects `NetworkServer` to collect ciphertexts
+    /// under. Defaults to the flat star topology every existing deployment already uses;
+    /// `add_ciphertexts`'s fold over `pk.add_encrypted` applies unchanged to a `Tree` interior
+    /// node's subtree, since homomorphically combining a subtree's ciphertexts is the same operation as
+    /// combining all of them at the root.
+    fn topology(&self) -> AggregationTopology {
+        AggregationTopology::Flat
+    }
 }
 
 pub struct PartyServer {
@@ -28,6 +46,10 @@ pub struct PartyServer {
     pub(crate) k: usize,
     // sum of the sizes of the databases
     pub(crate) databases_size: usize,
+    // how ciphertexts are folded on their way from the parties to this server
+    pub(crate) topology: AggregationTopology,
+    // minimum number of partial decryptions needed to reconstruct a plaintext
+    pub(crate) threshold: usize,
 }
 
 impl PartyServerTrait for PartyServer {
@@ -58,12 +80,26 @@ impl PartyServerTrait for PartyServer {
         &self,
         lt_shares: &[PartialDecryption],
         gt_shares: &[PartialDecryption],
-    ) -> [Plaintext; 2] {
-        let (lt, gt) = rayon::join(
-            || self.pk.share_combine(lt_shares).unwrap(),
-            || self.pk.share_combine(gt_shares).unwrap(),
+        contributing: &[usize],
+    ) -> Result<([Plaintext; 2], Vec<usize>), Error> {
+        if contributing.len() < self.threshold {
+            return Err(Error::InsufficientShares { have: contributing.len(), need: self.threshold });
+        }
+        let ((lt, lt_excluded), (gt, gt_excluded)) = rayon::join(
+            || self.robust_combine(lt_shares, contributing),
+            || self.robust_combine(gt_shares, contributing),
         );
-        [lt, gt]
+        let mut excluded = lt_excluded;
+        for party in gt_excluded {
+            if !excluded.contains(&party) {
+                excluded.push(party);
+            }
+        }
+        Ok(([lt, gt], excluded))
+    }
+
+    fn topology(&self) -> AggregationTopology {
+        self.topology
     }
 
     fn calculate_update(&self, [lt, gt]: [Plaintext; 2]) -> UpdateSearchRange {
@@ -77,4 +113,68 @@ impl PartyServerTrait for PartyServer {
             UpdateSearchRange::FoundK
         }
     }
+}
+
+impl PartyServer {
+    /// Reconstructs a plaintext from the given shares, cross-checking them against each other
+    /// when more arrived than the reconstruction threshold requires.
+    ///
+    /// A single malicious party can submit a `PartialDecryption` that doesn't match its secret
+    /// key share, and `PublicKey::share_combine` has no way to tell: it just runs Lagrange
+    /// interpolation over whatever shares it's handed. The textbook fix is to have each party
+    /// attach a Chaum-Pedersen proof that its share was computed with the same exponent as its
+    /// published verification key, but `pht_crypto` doesn't expose the group exponentiation such
+    /// a proof needs (the same gap `party::dkg` hit building its Feldman commitment). What we
+    /// *can* check without it: every `threshold`-sized subset of honest shares reconstructs to
+    /// the same plaintext, so a forged share stands out as the odd one out among subsets that
+    /// disagree with the rest. With more than `threshold` shares available we therefore try every
+    /// such subset and keep whichever plaintext the most of them agree on, returning the indices
+    /// of the parties that never appear in an agreeing subset. With exactly `threshold` shares
+    /// there's no redundancy to check against at all, so that minimal case is trusted as before;
+    /// detecting a cheater there is exactly what the missing ZK proof would be for.
+    fn robust_combine(&self, shares: &[PartialDecryption], contributing: &[usize]) -> (Plaintext, Vec<usize>) {
+        if shares.len() <= self.threshold {
+            return (self.pk.share_combine(shares).unwrap(), Vec::new());
+        }
+
+        let mut tally: Vec<(Plaintext, Vec<usize>)> = Vec::new();
+        for subset in k_combinations(shares.len(), self.threshold) {
+            let subset_shares: Vec<PartialDecryption> = subset.iter().map(|&i| shares[i].clone()).collect();
+            let plaintext = match self.pk.share_combine(&subset_shares) {
+                Ok(plaintext) => plaintext,
+                Err(_) => continue,
+            };
+            let agreeing_parties: Vec<usize> = subset.iter().map(|&i| contributing[i]).collect();
+            match tally.iter_mut().find(|(p, _)| *p == plaintext) {
+                Some((_, parties)) => parties.extend(agreeing_parties),
+                None => tally.push((plaintext, agreeing_parties)),
+            }
+        }
+
+        let (winner, agreeing_parties) = tally
+            .into_iter()
+            .max_by_key(|(_, parties)| parties.len())
+            .expect("contributing is non-empty, so at least one subset was evaluated");
+        let excluded = contributing.iter().filter(|party| !agreeing_parties.contains(party)).copied().collect();
+        (winner, excluded)
+    }
+}
+
+/// Every way to choose `k` indices out of `0..n`, used to enumerate the subsets `robust_combine`
+/// cross-checks. Exhaustive, so only fit for the small party counts this protocol targets.
+fn k_combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    if k > n {
+        return Vec::new();
+    }
+    let mut combinations = Vec::new();
+    for last in (k - 1)..n {
+        for mut combination in k_combinations(last, k - 1) {
+            combination.push(last);
+            combinations.push(combination);
+        }
+    }
+    combinations
 }
\ No newline at end of file
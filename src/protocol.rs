@@ -1,3 +1,4 @@
+use crate::party::dpf::DpfKey;
 use crate::party::party_client::PartyClientTrait;
 use crate::party::party_server::PartyServerTrait;
 use crate::party::TypeTrait;
@@ -47,7 +48,19 @@ pub fn leaky_kth_ranked_element<T, P, S>(server: &mut S, parties: &mut Vec<P>) -
         }
 
         // TODO: The "update" should be broadcasted to all parties.
-        let sums = server.combine_shares(&lt_shares, &gt_shares);
+        // Every party contributes in this non-networked loop, so `contributing` is just every
+        // index in order.
+        let contributing: Vec<usize> = (0..parties.len()).collect();
+        let (sums, inconsistent) = match server.combine_shares(&lt_shares, &gt_shares, &contributing) {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::warn!("Failed to combine partial decryptions: {}", e);
+                return None;
+            }
+        };
+        for party in inconsistent {
+            tracing::warn!("Party {} submitted a partial decryption inconsistent with the rest of the group", party);
+        }
         let update = server.calculate_update(sums);
         for party in &mut *parties {
             res = party.update_search_range(update);
@@ -60,4 +73,55 @@ pub fn leaky_kth_ranked_element<T, P, S>(server: &mut S, parties: &mut Vec<P>) -
         }
     }
     res
+}
+
+/// Privately retrieves the record backing the element `leaky_kth_ranked_element` just converged
+/// on, given each party's database of records (parallel to its sorted element database, so
+/// `records[i][j]` is the record for `parties[i]`'s `j`-th element).
+///
+/// At least one party's last `local_computation` matched the search midpoint exactly
+/// (`PartyClientTrait::owns_record`); the lowest-indexed such party is the dealer and
+/// secret-shares a [`DpfKey`]
+/// point function that is `beta` (the record) at its winning local index and `0` everywhere
+/// else, for every party's database. The dealer's two resulting key halves are handed to
+/// [`eval_share`], standing in for the two non-colluding destinations a DPF share is normally
+/// evaluated towards: `eval_share` only ever sees its own keys, never `alpha`, `beta`, or the
+/// other half's keys, so its running total alone is indistinguishable from random and leaks
+/// neither which party nor which index the dealer's point is at. Only XORing the two evaluators'
+/// totals together reveals the record. There is no networking yet to actually send each half to
+/// a separate party (same open work as the rest of this module), so both calls still run
+/// in-process here — but `alpha`/`beta` go out of scope with key generation and nothing past that
+/// point can see them, unlike folding both halves inline in one loop. Returns `None` if no party
+/// reports owning the element (e.g. the search never converged).
+pub fn retrieve_record<T, P>(parties: &[P], records: &[Vec<u64>]) -> Option<u64>
+    where
+        T: TypeTrait,
+        P: PartyClientTrait<T>,
+{
+    let dealer_idx = parties.iter().position(|party| party.owns_record().is_some())?;
+    let local_idx = parties[dealer_idx].owns_record().expect("just confirmed Some above");
+    let beta = records[dealer_idx][local_idx];
+
+    let mut keys_a = Vec::with_capacity(records.len());
+    let mut keys_b = Vec::with_capacity(records.len());
+    for (i, db) in records.iter().enumerate() {
+        if db.is_empty() {
+            continue;
+        }
+        let (alpha, beta) = if i == dealer_idx { (local_idx, beta) } else { (0, 0) };
+        let (key_a, key_b) = DpfKey::gen(db.len(), alpha, beta);
+        keys_a.push(key_a);
+        keys_b.push(key_b);
+    }
+    Some(eval_share(&keys_a) ^ eval_share(&keys_b))
+}
+
+/// One of the two non-colluding destinations a DPF share is normally evaluated towards: expands
+/// every key in `keys` across its own domain and folds the results into a single running total.
+/// Takes only the keys its own evaluator was handed, never `alpha`, `beta`, or the other
+/// destination's keys, so the returned total alone is indistinguishable from random.
+fn eval_share(keys: &[DpfKey]) -> u64 {
+    keys.iter()
+        .flat_map(|key| (0..key.domain_size()).map(|x| key.eval(x)))
+        .fold(0, |acc, v| acc ^ v)
 }
\ No newline at end of file
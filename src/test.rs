@@ -53,7 +53,8 @@ pub mod tests {
         let [lt, gt] = p.local_computation();
         let [sum_lt_enc, sum_gt_enc] = s.add_ciphertexts(&[lt], &[gt]);
         let [lt_share, gt_share] = p.compute_shares(sum_lt_enc, sum_gt_enc);
-        let [sum_lt, sum_gt] = s.combine_shares(&[lt_share], &[gt_share]);
+        let ([sum_lt, sum_gt], inconsistent) = s.combine_shares(&[lt_share], &[gt_share], &[0]).unwrap();
+        assert!(inconsistent.is_empty());
         assert_eq!(sum_lt, Integer::from(exp_sum_lt));
         assert_eq!(sum_gt, Integer::from(exp_sum_gt));
     }
@@ -7,7 +7,10 @@ use rand::distributions::uniform::SampleUniform;
 use rand_distr::Normal;
 use rug::rand::RandState;
 
-use crate::party::dp_client::{DPClient, GetScaleFn, NoiseLevel};
+use crate::net::gossip::{weighted_shuffle, ContactInfo, Membership};
+use crate::net::topology::AggregationTopology;
+use crate::party::dkg::DkgParty;
+use crate::party::dp_client::{DPClient, GetScaleFn, NoiseLevel, NoiseMechanism};
 use crate::party::party_client::PartyClient;
 use crate::party::party_server::PartyServer;
 use crate::party::TypeTrait;
@@ -71,6 +74,8 @@ pub(crate) fn create_single_party(db: Vec<i32>) -> (PartyServer, PartyClient<i32
         pk,
         k,
         databases_size,
+        topology: AggregationTopology::Flat,
+        threshold: 1,
     };
     (server, client)
 }
@@ -112,16 +117,143 @@ pub fn create_server_clients(k: usize, databases: Vec<Vec<i32>>) -> (PartyServer
         pk,
         k,
         databases_size,
+        topology: AggregationTopology::Flat,
+        threshold: n,
     };
     (server, clients)
 }
 
-/// Creates a server and multiple party clients that use differential privacy.
+/// Creates a server and multiple party clients whose threshold key comes from a dealer the
+/// parties themselves verified and selected via [`DkgParty`], instead of one fixed in advance.
+///
+/// Every party deals its own polynomial and commits to the shares it owes the others; here, with
+/// all parties in-process, every share is "sent" and verified immediately instead of over a
+/// network round-trip, and the lowest-indexed dealer no party complained about becomes the key's
+/// source. That dealer still single-handedly generated the key pair and holds its complete
+/// secret key — see [`crate::party::dkg`] for why this selects a dealer rather than eliminating
+/// one.
+pub fn create_server_clients_dkg(k: usize, databases: Vec<Vec<i32>>, threshold: usize) -> (PartyServer, Vec<PartyClient<i32>>) {
+    let n = databases.len();
+    let databases_size = databases.iter().map(|db| db.len()).sum();
+    let min = databases.iter().map(|db| db.iter().min().unwrap()).min().unwrap();
+    let max = databases.iter().map(|db| db.iter().max().unwrap()).max().unwrap();
+    let search_range = [*min, *max];
+
+    let mut parties: Vec<DkgParty> = (0..n).map(|idx| DkgParty::new(idx as u32, n as u32, threshold as u32)).collect();
+    let mut rand = RandState::new();
+    // Every party deals once; with all parties in-process this stands in for each dealer
+    // broadcasting its commitment and sending shares over the network.
+    let dealt: Vec<_> = parties.iter().map(|dealer| dealer.deal(&mut rand)).collect();
+
+    // Every party verifies every share addressed to it and complains; here a complaint against a
+    // dealer disqualifies it for all parties at once, since the commitment is public.
+    for (commitment, shares) in &dealt {
+        for share in shares {
+            let recipient = &parties[share.recipient as usize];
+            if let Some(complaint) = recipient.verify_share(commitment, share) {
+                for party in parties.iter_mut() {
+                    party.disqualify(complaint.accused);
+                }
+            }
+        }
+    }
+
+    let clients = databases
+        .into_iter()
+        .enumerate()
+        .map(|(idx, db)| {
+            let received: Vec<_> = dealt.iter().map(|(_, shares)| shares[idx].clone()).collect();
+            let (pk, key_share) = parties[idx].finalize(&received).expect("at least one dealer is always qualified");
+            let rand = RandState::new();
+            PartyClient::new(
+                db,
+                idx as u32,
+                n,
+                k,
+                databases_size,
+                search_range.clone(),
+                pk,
+                key_share,
+                rand,
+            )
+        })
+        .collect();
+    // Every qualified party agrees on the same winning dealer, so the server can derive its
+    // view of the joint `PublicKey` the same way.
+    let received: Vec<_> = dealt.iter().map(|(_, shares)| shares[0].clone()).collect();
+    let (pk, _) = parties[0].finalize(&received).expect("at least one dealer is always qualified");
+    let server = PartyServer {
+        n,
+        pk,
+        k,
+        databases_size,
+        topology: AggregationTopology::Flat,
+        threshold,
+    };
+    (server, clients)
+}
+
+/// Creates a server and multiple party clients configured to aggregate ciphertexts over a
+/// balanced tree of the given `fan_out` instead of the flat star topology. Only the server's
+/// `topology` changes: no party relays or folds its children's ciphertexts, so `NetworkServer`
+/// still collects every party's ciphertext directly (see `net::topology`).
+pub fn create_server_clients_tree(k: usize, databases: Vec<Vec<i32>>, fan_out: usize) -> (PartyServer, Vec<PartyClient<i32>>) {
+    let (mut server, clients) = create_server_clients(k, databases);
+    server.topology = AggregationTopology::Tree { fan_out };
+    (server, clients)
+}
+
+/// Creates a server and multiple party clients configured to aggregate ciphertexts over a tree
+/// whose layer assignment comes from gossiped membership rather than raw party id order.
+///
+/// Stands in for every party first gossiping its [`crate::net::gossip::ContactInfo`] with its
+/// peers until a [`crate::net::gossip::Membership`] converges (here all parties already know
+/// each other, so a single merge reaches that fixed point); `weights` is each party's stake for
+/// the [`crate::net::gossip::weighted_shuffle`] A-Res draw (e.g. inverse latency or bandwidth),
+/// heavier parties landing earlier in the tree with higher probability. Returns, alongside the
+/// usual server and clients, each party's resulting position in the tree so it can be handed to
+/// `NetworkClient::new_with_topology` as `idx` — that position is a function of the gossiped
+/// stake-weighted order, not the party's index into `databases`.
+pub fn create_server_clients_gossip_tree(
+    k: usize, databases: Vec<Vec<i32>>, fan_out: usize, weights: &[f64],
+) -> (PartyServer, Vec<PartyClient<i32>>, Vec<usize>) {
+    assert_eq!(weights.len(), databases.len(), "one weight is needed per party");
+    let n = databases.len();
+
+    let mut membership = Membership::new();
+    for (party, _) in databases.iter().enumerate() {
+        membership.merge(party, ContactInfo { address: format!("party-{party}"), version: 0 });
+    }
+    debug_assert_eq!(membership.len(), n);
+
+    let order = weighted_shuffle(weights, &mut thread_rng());
+    // `order[pos]` is the party placed at tree position `pos`; invert it so `positions[party]`
+    // is that party's own tree position.
+    let mut positions = vec![0; n];
+    for (pos, &party) in order.iter().enumerate() {
+        positions[party] = pos;
+    }
+
+    let (mut server, clients) = create_server_clients(k, databases);
+    server.topology = AggregationTopology::Tree { fan_out };
+    (server, clients, positions)
+}
+
+/// Creates a server and multiple party clients that use differential privacy, via the
+/// continuous Laplace mechanism every existing caller already expects. Use
+/// [`create_server_dp_clients_with_mechanism`] to opt into [`NoiseMechanism::Discrete`] instead.
 pub fn create_server_dp_clients(k: usize, databases: Vec<Vec<i32>>, get_scale_fn: GetScaleFn,
                                 noise_level: NoiseLevel) -> (PartyServer, Vec<DPClient<i32>>) {
+    create_server_dp_clients_with_mechanism(k, databases, get_scale_fn, noise_level, NoiseMechanism::Continuous)
+}
+
+/// Creates a server and multiple party clients that use differential privacy under the given
+/// [`NoiseMechanism`].
+pub fn create_server_dp_clients_with_mechanism(k: usize, databases: Vec<Vec<i32>>, get_scale_fn: GetScaleFn,
+                                noise_level: NoiseLevel, mechanism: NoiseMechanism) -> (PartyServer, Vec<DPClient<i32>>) {
     let (server, clients) = create_server_clients(k, databases);
     let dp_clients = clients.into_iter().map(|client| -> DPClient<i32> {
-        DPClient::new(client, get_scale_fn, noise_level)
+        DPClient::new_with_mechanism(client, get_scale_fn, noise_level, mechanism)
     }).collect();
     (server, dp_clients)
 }